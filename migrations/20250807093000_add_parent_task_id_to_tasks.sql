@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE tasks ADD COLUMN parent_task_id BIGINT REFERENCES tasks(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
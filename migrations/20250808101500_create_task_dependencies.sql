@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE task_dependencies (
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    blocked_by_task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (task_id, blocked_by_task_id),
+    CHECK (task_id <> blocked_by_task_id)
+);
+
+CREATE INDEX idx_task_dependencies_blocked_by_task_id ON task_dependencies(blocked_by_task_id);
@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE task_comments (
+    id BIGSERIAL PRIMARY KEY,
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    parent_comment_id BIGINT REFERENCES task_comments(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    edited_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_task_comments_task_id ON task_comments(task_id, created_at);
+CREATE INDEX idx_task_comments_parent_comment_id ON task_comments(parent_comment_id);
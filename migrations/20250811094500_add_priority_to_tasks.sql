@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TYPE task_priority AS ENUM ('low', 'medium', 'high', 'urgent');
+
+ALTER TABLE tasks ADD COLUMN priority task_priority NOT NULL DEFAULT 'medium';
+
+CREATE INDEX idx_tasks_priority ON tasks(priority);
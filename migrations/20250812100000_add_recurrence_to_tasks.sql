@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE tasks
+ADD COLUMN recurrence_rule TEXT,
+ADD COLUMN recurrence_timezone VARCHAR(64),
+ADD COLUMN recurrence_start TIMESTAMPTZ;
@@ -0,0 +1,16 @@
+-- Add migration script here
+-- Lexicographic rank within (project_id, status); compared byte-wise.
+ALTER TABLE tasks ADD COLUMN position TEXT COLLATE "C";
+
+-- Existing tasks keep their creation order. Ranks must not end in '0'.
+UPDATE tasks t
+SET position = lpad(to_hex(ranked.rn), 8, '0') || 'i'
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY project_id, status ORDER BY created_at, id) AS rn
+    FROM tasks
+) ranked
+WHERE ranked.id = t.id;
+
+ALTER TABLE tasks ALTER COLUMN position SET NOT NULL;
+
+CREATE INDEX idx_tasks_position ON tasks(project_id, status, position);
@@ -0,0 +1,35 @@
+-- Add migration script here
+CREATE TYPE status_category AS ENUM ('todo', 'doing', 'done');
+
+CREATE TABLE workflow_statuses (
+    id BIGSERIAL PRIMARY KEY,
+    project_id BIGINT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    key VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    category status_category NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_id, key)
+);
+
+-- Every existing project gets the default workflow, whose keys match the old
+-- task_status values so tasks keep their status.
+INSERT INTO workflow_statuses (project_id, key, name, category, position)
+SELECT p.id, s.key, s.name, s.category::status_category, s.position
+FROM projects p
+CROSS JOIN (VALUES
+    ('pending', 'Pending', 'todo', 0),
+    ('in_progress', 'In Progress', 'doing', 1),
+    ('completed', 'Completed', 'done', 2)
+) AS s(key, name, category, position);
+
+ALTER TABLE tasks ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE tasks ALTER COLUMN status TYPE VARCHAR(50) USING status::TEXT;
+
+-- Renaming a status key carries over to its tasks; a status still in use
+-- cannot be deleted.
+ALTER TABLE tasks
+ADD CONSTRAINT tasks_workflow_status_fkey
+FOREIGN KEY (project_id, status) REFERENCES workflow_statuses(project_id, key) ON UPDATE CASCADE;
+
+DROP TYPE task_status;
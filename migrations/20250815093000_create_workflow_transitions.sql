@@ -0,0 +1,10 @@
+-- Add migration script here
+-- Allowed status changes. A status without outgoing rows is unrestricted.
+CREATE TABLE workflow_transitions (
+    from_status_id BIGINT NOT NULL REFERENCES workflow_statuses(id) ON DELETE CASCADE,
+    to_status_id BIGINT NOT NULL REFERENCES workflow_statuses(id) ON DELETE CASCADE,
+    PRIMARY KEY (from_status_id, to_status_id),
+    CHECK (from_status_id <> to_status_id)
+);
+
+CREATE INDEX idx_workflow_transitions_to_status_id ON workflow_transitions(to_status_id);
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE time_entries (
+    id BIGSERIAL PRIMARY KEY,
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    -- NULL while the timer is running.
+    ended_at TIMESTAMPTZ,
+    duration_seconds BIGINT GENERATED ALWAYS AS (EXTRACT(EPOCH FROM (ended_at - started_at))::BIGINT) STORED,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+CREATE INDEX idx_time_entries_task_id ON time_entries(task_id, started_at);
+
+-- At most one running timer per user.
+CREATE UNIQUE INDEX idx_time_entries_running_timer ON time_entries(user_id) WHERE ended_at IS NULL;
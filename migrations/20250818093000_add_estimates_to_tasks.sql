@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE tasks
+ADD COLUMN estimate_minutes INTEGER CHECK (estimate_minutes >= 0),
+ADD COLUMN story_points INTEGER CHECK (story_points >= 0);
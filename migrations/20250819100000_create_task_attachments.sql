@@ -0,0 +1,33 @@
+-- Add migration script here
+CREATE TABLE task_attachments (
+    id BIGSERIAL PRIMARY KEY,
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    uploaded_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    checksum_sha256 CHAR(64) NOT NULL,
+    storage_key VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_task_attachments_task_id ON task_attachments(task_id);
+
+-- Blobs whose metadata row is gone, including rows removed by a task or
+-- project cascade. A background job deletes them from storage.
+CREATE TABLE attachment_blob_deletions (
+    id BIGSERIAL PRIMARY KEY,
+    storage_key VARCHAR(255) NOT NULL,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE FUNCTION queue_attachment_blob_deletion() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO attachment_blob_deletions (storage_key) VALUES (OLD.storage_key);
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER task_attachments_queue_blob_deletion
+AFTER DELETE ON task_attachments
+FOR EACH ROW EXECUTE FUNCTION queue_attachment_blob_deletion();
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TYPE activity_action AS ENUM ('created', 'updated', 'deleted');
+
+-- History outlives the task it describes, so task_id is not a foreign key.
+CREATE TABLE task_activities (
+    id BIGSERIAL PRIMARY KEY,
+    project_id BIGINT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    task_id BIGINT NOT NULL,
+    task_title VARCHAR(255) NOT NULL,
+    actor_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    action activity_action NOT NULL,
+    -- Changed fields as {"field": {"from": ..., "to": ...}}.
+    changes JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_task_activities_task_id ON task_activities(task_id, created_at);
+CREATE INDEX idx_task_activities_project_id ON task_activities(project_id, created_at);
@@ -0,0 +1,34 @@
+-- Add migration script here
+CREATE TABLE project_templates (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    -- Workflow statuses in board order as [{"key", "name", "category"}].
+    workflow JSONB NOT NULL DEFAULT '[]',
+    -- Project labels as [{"name", "color"}].
+    labels JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_project_templates_user_id ON project_templates(user_id);
+
+-- Tasks created when a template is instantiated, in creation order. Parents
+-- always come before their subtasks.
+CREATE TABLE template_tasks (
+    id BIGSERIAL PRIMARY KEY,
+    template_id BIGINT NOT NULL REFERENCES project_templates(id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    parent_position INT,
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    priority task_priority NOT NULL DEFAULT 'medium',
+    -- Due date relative to the anchor date the template is instantiated with.
+    due_offset_minutes BIGINT,
+    estimate_minutes INT,
+    story_points INT,
+    label_names TEXT[] NOT NULL DEFAULT '{}',
+    checklist TEXT[] NOT NULL DEFAULT '{}',
+    UNIQUE (template_id, position),
+    CHECK (parent_position < position)
+);
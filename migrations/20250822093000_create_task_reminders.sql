@@ -0,0 +1,19 @@
+-- Add migration script here
+-- One row per task, threshold and due date: the unique key is what makes a
+-- reminder fire once, across restarts and concurrent workers. Moving the due
+-- date arms the thresholds again.
+CREATE TABLE task_reminders (
+    id BIGSERIAL PRIMARY KEY,
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    threshold_minutes INT NOT NULL,
+    due_date TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- NULL until delivered or skipped.
+    processed_at TIMESTAMPTZ,
+    -- Not delivered because the task was completed, its due date moved or a
+    -- closer threshold was reached first.
+    skipped BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (task_id, threshold_minutes, due_date)
+);
+
+CREATE INDEX idx_task_reminders_pending ON task_reminders(due_date) WHERE processed_at IS NULL;
@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Allowed workflow transitions as [{"from", "to"}] status keys.
+ALTER TABLE project_templates
+ADD COLUMN transitions JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,6 @@
+-- Add migration script here
+-- Set, and committed, before a reminder is handed to the notifier, so a crash
+-- or a failed write after delivery cannot send it a second time. A failed
+-- delivery releases the claim for a retry; a crash between claim and delivery
+-- leaves the reminder claimed and unsent rather than risking a duplicate.
+ALTER TABLE task_reminders ADD COLUMN claimed_at TIMESTAMPTZ;
@@ -0,0 +1,17 @@
+-- Add migration script here
+-- A restricted status only allows the transitions listed for it, so one
+-- without any is terminal. Statuses that already list transitions stay
+-- restricted to them.
+ALTER TABLE workflow_statuses ADD COLUMN restricted BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE workflow_statuses ws SET restricted = TRUE
+WHERE EXISTS (SELECT 1 FROM workflow_transitions wt WHERE wt.from_status_id = ws.id);
+
+-- Templates capture the flag with each status.
+UPDATE project_templates pt SET workflow = (
+    SELECT jsonb_agg(
+        w.status || jsonb_build_object('restricted', EXISTS (
+            SELECT 1 FROM jsonb_array_elements(pt.transitions) t WHERE t->>'from' = w.status->>'key'))
+        ORDER BY w.ord)
+    FROM jsonb_array_elements(pt.workflow) WITH ORDINALITY AS w(status, ord))
+WHERE jsonb_array_length(pt.workflow) > 0;
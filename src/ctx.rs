@@ -1,6 +1,20 @@
-use crate::web::user::User;
+use chrono::{DateTime, Utc};
+
+use crate::web::user::{Role, User};
 
 #[derive(Clone)]
 pub struct Ctx {
     pub user: User,
+    pub jti: String,
+    pub token_expires_at: DateTime<Utc>,
+}
+
+impl Ctx {
+    pub fn role(&self) -> Role {
+        self.user.role
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role() == Role::Admin
+    }
 }
@@ -1,6 +1,6 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+use serde_json::{json, Value};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -8,42 +8,178 @@ pub type Result<T> = core::result::Result<T, Error>;
 pub enum Error {
     LoginFail,
     AuthFail,
+    RefreshTokenInvalid,
+    RefreshTokenReused,
     SqlxError(sqlx::Error),
     ProjectNotFound,
     ProjectUnauthorized,
+    MemberNotFound,
+    MemberAlreadyExists,
+    LastProjectOwner,
+    InvitationNotFound,
+    InvitationInvalid,
+    InvitationExpiryInvalid,
     TaskNotFound,
     TaskUnauthorized,
+    AssigneeNotMember,
+    LabelNotFound,
+    LabelAlreadyExists,
+    LabelNameEmpty,
+    InvalidLabelColor,
+    LabelNotInProject,
+    WorkflowStatusNotFound,
+    WorkflowStatusAlreadyExists,
+    WorkflowStatusInUse,
+    LastWorkflowStatus,
+    InvalidStatusKey,
+    StatusNameEmpty,
+    InvalidTaskStatus,
+    /// `allowed` lists the status keys the task may move to from `from`.
+    TransitionNotAllowed {
+        from: String,
+        to: String,
+        allowed: Vec<String>,
+    },
+    CommentNotFound,
+    CommentUnauthorized,
+    CommentEmpty,
+    InvalidParentComment,
+    ChecklistItemNotFound,
+    ChecklistItemEmpty,
+    InvalidChecklistOrder,
+    TimeEntryNotFound,
+    TimeEntryUnauthorized,
+    TimerAlreadyRunning,
+    NoRunningTimer,
+    InvalidTimeRange,
+    InvalidEstimate,
+    AttachmentNotFound,
+    AttachmentUnauthorized,
+    AttachmentTooLarge,
+    AttachmentMissing,
+    InvalidMultipart,
+    InvalidParentTask,
+    TaskHierarchyCycle,
+    TaskDependencyCycle,
+    DependencyNotFound,
+    TaskBlocked,
+    InvalidRecurrenceRule,
+    InvalidTimezone,
+    RecurrenceRequiresDueDate,
+    TaskNotRecurring,
+    InvalidMoveTarget,
+    BulkTargetMissing,
+    BulkTooManyTasks,
+    BulkFilterUnsupported,
+    InvalidPagination,
+    TemplateNotFound,
     UserNotFound,
+    UserUnauthorized,
+    AdminRequired,
     AnyhowError(anyhow::Error),
 }
 
-impl IntoResponse for Error {
-    fn into_response(self) -> Response {
-        println!("->> {:<12} - {self:?}", "INTO_RES");
+impl Error {
+    /// The status code and JSON body this error is reported with.
+    pub fn status_and_body(self) -> (StatusCode, Value) {
+        // Extra, error-specific information returned next to the message.
+        let mut details = None;
 
         let (status, error_message) = match self {
             Error::LoginFail => (StatusCode::UNAUTHORIZED, "Login failed"),
             Error::AuthFail => (StatusCode::UNAUTHORIZED, "Authentication failed"),
+            Error::RefreshTokenInvalid => (StatusCode::UNAUTHORIZED, "Invalid refresh token"),
+            Error::RefreshTokenReused => (StatusCode::UNAUTHORIZED, "Refresh token reuse detected, session revoked"),
             Error::SqlxError(err) => {
                 eprintln!("->> SQLX Error: {err:?}");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
             }
             Error::ProjectNotFound => (StatusCode::NOT_FOUND, "Project not found"),
             Error::ProjectUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to project"),
+            Error::MemberNotFound => (StatusCode::NOT_FOUND, "Project member not found"),
+            Error::MemberAlreadyExists => (StatusCode::CONFLICT, "User is already a project member"),
+            Error::LastProjectOwner => (StatusCode::CONFLICT, "Project must keep at least one owner"),
+            Error::InvitationNotFound => (StatusCode::NOT_FOUND, "Pending invitation not found"),
+            Error::InvitationInvalid => (StatusCode::BAD_REQUEST, "Invalid or expired invitation"),
+            Error::InvitationExpiryInvalid => (StatusCode::BAD_REQUEST, "Invitation expiry must be a positive number of hours"),
             Error::TaskNotFound => (StatusCode::NOT_FOUND, "Task not found"),
             Error::TaskUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to task"),
+            Error::InvalidParentTask => (StatusCode::UNPROCESSABLE_ENTITY, "Parent task must belong to the same project"),
+            Error::TaskHierarchyCycle => (StatusCode::UNPROCESSABLE_ENTITY, "A task cannot be nested under itself or its own subtasks"),
+            Error::TaskDependencyCycle => (StatusCode::UNPROCESSABLE_ENTITY, "Dependency would create a cycle"),
+            Error::DependencyNotFound => (StatusCode::NOT_FOUND, "Dependency not found"),
+            Error::TaskBlocked => (StatusCode::CONFLICT, "Task is blocked by unfinished dependencies"),
+            Error::InvalidRecurrenceRule => (StatusCode::UNPROCESSABLE_ENTITY, "Invalid or unsupported recurrence rule"),
+            Error::InvalidTimezone => (StatusCode::UNPROCESSABLE_ENTITY, "Unknown time zone"),
+            Error::RecurrenceRequiresDueDate => (StatusCode::UNPROCESSABLE_ENTITY, "Recurring tasks need a due date"),
+            Error::TaskNotRecurring => (StatusCode::UNPROCESSABLE_ENTITY, "Task is not recurring"),
+            Error::InvalidMoveTarget => (StatusCode::UNPROCESSABLE_ENTITY, "Neighbour tasks must be other tasks in the target column, in order"),
+            Error::AssigneeNotMember => (StatusCode::UNPROCESSABLE_ENTITY, "Assignees must be members of the task's project"),
+            Error::LabelNotFound => (StatusCode::NOT_FOUND, "Label not found"),
+            Error::LabelAlreadyExists => (StatusCode::CONFLICT, "A label with this name already exists in the project"),
+            Error::LabelNameEmpty => (StatusCode::UNPROCESSABLE_ENTITY, "Label name must not be empty"),
+            Error::InvalidLabelColor => (StatusCode::UNPROCESSABLE_ENTITY, "Label color must be a hex color like #1f883d"),
+            Error::LabelNotInProject => (StatusCode::UNPROCESSABLE_ENTITY, "Labels must belong to the task's project"),
+            Error::WorkflowStatusNotFound => (StatusCode::NOT_FOUND, "Workflow status not found"),
+            Error::WorkflowStatusAlreadyExists => (StatusCode::CONFLICT, "A status with this key already exists in the project"),
+            Error::WorkflowStatusInUse => (StatusCode::CONFLICT, "Status is still used by tasks"),
+            Error::LastWorkflowStatus => (StatusCode::CONFLICT, "A workflow needs at least one status"),
+            Error::InvalidStatusKey => (StatusCode::UNPROCESSABLE_ENTITY, "Status keys must be 1-50 lowercase letters, digits or underscores"),
+            Error::StatusNameEmpty => (StatusCode::UNPROCESSABLE_ENTITY, "Status name must not be empty"),
+            Error::InvalidTaskStatus => (StatusCode::UNPROCESSABLE_ENTITY, "Status is not part of the project's workflow"),
+            Error::TransitionNotAllowed { from, to, allowed } => {
+                details = Some(json!({ "from": from, "to": to, "allowed_transitions": allowed }));
+                (StatusCode::UNPROCESSABLE_ENTITY, "Status transition is not allowed by the project's workflow")
+            }
+            Error::CommentNotFound => (StatusCode::NOT_FOUND, "Comment not found"),
+            Error::CommentUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to comment"),
+            Error::CommentEmpty => (StatusCode::UNPROCESSABLE_ENTITY, "Comment body must not be empty"),
+            Error::InvalidParentComment => (StatusCode::UNPROCESSABLE_ENTITY, "Replies must target a top-level comment on the same task"),
+            Error::ChecklistItemNotFound => (StatusCode::NOT_FOUND, "Checklist item not found"),
+            Error::ChecklistItemEmpty => (StatusCode::UNPROCESSABLE_ENTITY, "Checklist item text must not be empty"),
+            Error::InvalidChecklistOrder => (StatusCode::UNPROCESSABLE_ENTITY, "Item order must list every checklist item of the task exactly once"),
+            Error::TimeEntryNotFound => (StatusCode::NOT_FOUND, "Time entry not found"),
+            Error::TimeEntryUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to time entry"),
+            Error::TimerAlreadyRunning => (StatusCode::CONFLICT, "A timer is already running; stop it first"),
+            Error::NoRunningTimer => (StatusCode::NOT_FOUND, "No running timer on this task"),
+            Error::InvalidTimeRange => (StatusCode::UNPROCESSABLE_ENTITY, "A time entry must end after it starts"),
+            Error::InvalidEstimate => (StatusCode::UNPROCESSABLE_ENTITY, "Estimates and story points must not be negative"),
+            Error::AttachmentNotFound => (StatusCode::NOT_FOUND, "Attachment not found"),
+            Error::AttachmentUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to attachment"),
+            Error::AttachmentTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, "Attachment exceeds the maximum file size"),
+            Error::AttachmentMissing => (StatusCode::UNPROCESSABLE_ENTITY, "Upload must include a `file` field"),
+            Error::InvalidMultipart => (StatusCode::BAD_REQUEST, "Invalid multipart body"),
+            Error::BulkTargetMissing => (StatusCode::UNPROCESSABLE_ENTITY, "Provide either `task_ids` or `filter`"),
+            Error::BulkTooManyTasks => (StatusCode::UNPROCESSABLE_ENTITY, "Bulk operations are limited to 500 tasks"),
+            Error::BulkFilterUnsupported => (StatusCode::UNPROCESSABLE_ENTITY, "Bulk filters do not take `page`, `limit` or `sort`"),
+            Error::InvalidPagination => (StatusCode::BAD_REQUEST, "`page` and `limit` must be at least 1"),
+            Error::TemplateNotFound => (StatusCode::NOT_FOUND, "Template not found"),
             Error::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+            Error::UserUnauthorized => (StatusCode::FORBIDDEN, "Forbidden access to user"),
+            Error::AdminRequired => (StatusCode::FORBIDDEN, "Admin role required"),
             Error::AnyhowError(err) => {
                 eprintln!("->> Anyhow Error: {err:?}");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             }
         };
 
-        let body = json!({
+        let mut body = json!({
             "status": "error",
             "message": error_message,
         });
+        if let Some(details) = details {
+            body["details"] = details;
+        }
+
+        (status, body)
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        println!("->> {:<12} - {self:?}", "INTO_RES");
 
+        let (status, body) = self.status_and_body();
         (status, axum::Json(body)).into_response()
     }
 }
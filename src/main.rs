@@ -5,23 +5,43 @@ use axum::{
     Router,
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::services::ServeDir;
 mod ctx;
 mod errors;
 mod web;
+use web::attachment::storage::{LocalStorage, Storage};
 use web::db::{new_db_pool, Db};
+use web::reminder::notifier::notifier_from_env;
+use web::reminder::ReminderConfig;
 
 #[tokio::main]
 async fn main() {
     dotenv::dotenv().ok();
     let db: Db = new_db_pool().await.expect("Failed to create database pool");
+    web::auth::revocation::spawn_revocation_sweeper(db.clone());
+    let storage: Storage = Arc::new(LocalStorage::from_env());
+    web::attachment::spawn_blob_sweeper(db.clone(), storage.clone());
+    web::reminder::spawn_reminder_worker(db.clone(), ReminderConfig::from_env(), notifier_from_env());
 
     let public_routes = web::auth::routes(db.clone());
 
     let protected_routes = Router::new()
         .route("/me", get(web::auth::me))
+        .merge(web::auth::protected_routes(db.clone()))
         .merge(web::project::routes::routes(db.clone()))
+        .merge(web::member::routes::routes(db.clone()))
+        .merge(web::invitation::routes::routes(db.clone()))
         .merge(web::task::routes::routes(db.clone()))
+        .merge(web::dependency::routes::routes(db.clone()))
+        .merge(web::comment::routes::routes(db.clone()))
+        .merge(web::checklist::routes::routes(db.clone()))
+        .merge(web::time_entry::routes::routes(db.clone()))
+        .merge(web::attachment::routes::routes(db.clone(), storage.clone()))
+        .merge(web::activity::routes::routes(db.clone()))
+        .merge(web::template::routes::routes(db.clone()))
+        .merge(web::label::routes::routes(db.clone()))
+        .merge(web::workflow::routes::routes(db.clone()))
         .merge(web::user::routes::routes(db.clone()))
         .route_layer(middleware::from_fn_with_state(
             db.clone(),
@@ -0,0 +1,145 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::{FromRow, PgConnection, Type};
+use utoipa::ToSchema;
+
+use crate::web::task::Task;
+use crate::Result;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "activity_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// One recorded change to a task.
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct TaskActivity {
+    pub id: i64,
+    pub project_id: i64,
+    pub task_id: i64,
+    /// The task's title when the change was made.
+    pub task_title: String,
+    pub actor_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub action: ActivityAction,
+    /// Changed fields as `{"field": {"from": ..., "to": ...}}`.
+    #[schema(value_type = Object)]
+    pub changes: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityListQueryParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+/// The task fields tracked in its history.
+const TRACKED_FIELDS: [&str; 13] = [
+    "project_id",
+    "title",
+    "description",
+    "status",
+    "priority",
+    "due_date",
+    "parent_task_id",
+    "recurrence_rule",
+    "recurrence_timezone",
+    "estimate_minutes",
+    "story_points",
+    "assignee_ids",
+    "label_ids",
+];
+
+/// Values of [`TRACKED_FIELDS`], all `null` for a missing task.
+fn tracked_values(task: Option<&Task>) -> [Value; 13] {
+    let Some(task) = task else {
+        return Default::default();
+    };
+    let mut label_ids: Vec<i64> = task.labels.iter().map(|label| label.id).collect();
+    label_ids.sort_unstable();
+
+    [
+        json!(task.project_id),
+        json!(task.title),
+        json!(task.description),
+        json!(task.status),
+        json!(task.priority),
+        json!(task.due_date),
+        json!(task.parent_task_id),
+        json!(task.recurrence_rule),
+        json!(task.recurrence_timezone),
+        json!(task.estimate_minutes),
+        json!(task.story_points),
+        json!(task.assignee_ids),
+        json!(label_ids),
+    ]
+}
+
+/// Field-level diff between two versions of a task. A missing side counts as
+/// unset, so a creation lists every field that was set and a deletion every
+/// field that was set before.
+fn diff_tasks(before: Option<&Task>, after: Option<&Task>) -> Map<String, Value> {
+    let mut changes = Map::new();
+    for ((field, from), to) in TRACKED_FIELDS
+        .into_iter()
+        .zip(tracked_values(before))
+        .zip(tracked_values(after))
+    {
+        if from != to && !(is_unset(&from) && is_unset(&to)) {
+            changes.insert(field.to_string(), json!({ "from": from, "to": to }));
+        }
+    }
+    changes
+}
+
+fn is_unset(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Records a change made by `actor_id` to a task. `before` is `None` for a
+/// creation and `after` is `None` for a deletion. Updates that did not change
+/// any tracked field are not recorded.
+pub(crate) async fn record_task_activity(
+    conn: &mut PgConnection,
+    actor_id: i64,
+    before: Option<&Task>,
+    after: Option<&Task>,
+) -> Result<()> {
+    let (task, action) = match (before, after) {
+        (None, Some(task)) => (task, ActivityAction::Created),
+        (Some(_), Some(task)) => (task, ActivityAction::Updated),
+        (Some(task), None) => (task, ActivityAction::Deleted),
+        (None, None) => return Ok(()),
+    };
+
+    let changes = diff_tasks(before, after);
+    if action == ActivityAction::Updated && changes.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO task_activities (project_id, task_id, task_title, actor_id, action, changes) VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(task.project_id)
+    .bind(task.id)
+    .bind(&task.title)
+    .bind(actor_id)
+    .bind(action)
+    .bind(Value::Object(changes))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
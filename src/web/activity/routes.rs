@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::activity::{ActivityListQueryParams, TaskActivity};
+use crate::web::db::Db;
+use crate::web::member::{require_project_role, require_task_role, ProjectRole};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route("/tasks/{id}/history", get(get_task_history))
+        .route("/projects/{id}/activity", get(get_project_activity))
+        .with_state(db)
+}
+
+const ACTIVITY_SELECT: &str = "SELECT a.id, a.project_id, a.task_id, a.task_title, a.actor_id, u.username AS actor_username, a.action, a.changes, a.created_at FROM task_activities a LEFT JOIN users u ON u.id = a.actor_id";
+
+/// A task's changes, newest first.
+async fn get_task_history(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Query(params): Query<ActivityListQueryParams>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let (page, limit, offset) = pagination(&params)?;
+
+    let activities: Vec<TaskActivity> = sqlx::query_as(&format!(
+        "{ACTIVITY_SELECT} WHERE a.task_id = $1 ORDER BY a.created_at DESC, a.id DESC LIMIT $2 OFFSET $3"
+    ))
+    .bind(task_id)
+    .bind(limit as i64)
+    .bind(offset)
+    .fetch_all(&db)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_activities WHERE task_id = $1")
+        .bind(task_id)
+        .fetch_one(&db)
+        .await?;
+
+    Ok(Json(activity_page(activities, total, page, limit)))
+}
+
+/// Changes to every task of the project, deleted ones included, newest first.
+async fn get_project_activity(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Query(params): Query<ActivityListQueryParams>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let (page, limit, offset) = pagination(&params)?;
+
+    let activities: Vec<TaskActivity> = sqlx::query_as(&format!(
+        "{ACTIVITY_SELECT} WHERE a.project_id = $1 ORDER BY a.created_at DESC, a.id DESC LIMIT $2 OFFSET $3"
+    ))
+    .bind(project_id)
+    .bind(limit as i64)
+    .bind(offset)
+    .fetch_all(&db)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_activities WHERE project_id = $1")
+        .bind(project_id)
+        .fetch_one(&db)
+        .await?;
+
+    Ok(Json(activity_page(activities, total, page, limit)))
+}
+
+/// Page, limit and row offset requested by `params`.
+fn pagination(params: &ActivityListQueryParams) -> Result<(u32, u32, i64)> {
+    let page = params.page.unwrap_or(1);
+    let limit = params.limit.unwrap_or(20);
+    if page == 0 || limit == 0 {
+        return Err(Error::InvalidPagination);
+    }
+    Ok((page, limit, (page as i64 - 1).saturating_mul(limit as i64)))
+}
+
+fn activity_page(activities: Vec<TaskActivity>, total: i64, page: u32, limit: u32) -> Value {
+    json!({
+        "status": "success",
+        "data": {
+            "activities": activities,
+            "pagination": {
+                "total": total,
+                "page": page,
+                "limit": limit,
+                "total_pages": (total as f64 / limit as f64).ceil() as u32
+            }
+        }
+    })
+}
@@ -0,0 +1,82 @@
+pub mod routes;
+pub mod storage;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::web::attachment::storage::Storage;
+use crate::web::db::Db;
+use crate::Result;
+
+const DEFAULT_MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+const BLOB_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+const BLOB_SWEEP_BATCH: i64 = 100;
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct Attachment {
+    pub id: i64,
+    pub task_id: i64,
+    /// `None` once the uploader's account has been deleted.
+    pub uploaded_by: Option<i64>,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex-encoded SHA-256 of the contents.
+    pub checksum_sha256: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Per-file upload limit, from `ATTACHMENT_MAX_BYTES` (10 MiB by default).
+pub fn max_attachment_bytes() -> usize {
+    std::env::var("ATTACHMENT_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_ATTACHMENT_BYTES)
+}
+
+/// Deletes blobs queued by the `task_attachments` delete trigger, one batch at
+/// a time. Returns how many were removed.
+pub async fn sweep_deleted_blobs(db: &Db, storage: &Storage) -> Result<usize> {
+    let mut tx = db.begin().await?;
+
+    let queued: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, storage_key FROM attachment_blob_deletions ORDER BY id LIMIT $1 FOR UPDATE SKIP LOCKED",
+    )
+    .bind(BLOB_SWEEP_BATCH)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut deleted_ids = Vec::with_capacity(queued.len());
+    for (id, key) in queued {
+        match storage.delete(&key).await {
+            Ok(()) => deleted_ids.push(id),
+            // Left in the queue and retried on the next sweep.
+            Err(err) => eprintln!("->> {:<12} - failed to delete blob {key}: {err:?}", "ATTACHMENTS"),
+        }
+    }
+
+    sqlx::query("DELETE FROM attachment_blob_deletions WHERE id = ANY($1)")
+        .bind(&deleted_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(deleted_ids.len())
+}
+
+/// Runs [`sweep_deleted_blobs`] periodically for the lifetime of the process.
+pub fn spawn_blob_sweeper(db: Db, storage: Storage) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BLOB_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = sweep_deleted_blobs(&db, &storage).await {
+                eprintln!("->> {:<12} - blob sweep failed: {err:?}", "ATTACHMENTS");
+            }
+        }
+    });
+}
@@ -0,0 +1,235 @@
+use axum::{
+    extract::{multipart::MultipartError, DefaultBodyLimit, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::attachment::storage::Storage;
+use crate::web::attachment::{max_attachment_bytes, Attachment};
+use crate::web::auth::refresh_token::generate_token;
+use crate::web::db::Db;
+use crate::web::member::{require_task_role, ProjectRole};
+
+/// Room for the multipart boundaries and part headers around the file.
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+#[derive(Clone)]
+struct AttachmentState {
+    db: Db,
+    storage: Storage,
+    max_bytes: usize,
+}
+
+pub fn routes(db: Db, storage: Storage) -> Router {
+    let max_bytes = max_attachment_bytes();
+
+    Router::new()
+        .route(
+            "/tasks/{id}/attachments",
+            get(list_attachments).post(upload_attachment),
+        )
+        .route(
+            "/tasks/{id}/attachments/{attachment_id}",
+            get(download_attachment).delete(delete_attachment),
+        )
+        .layer(DefaultBodyLimit::max(max_bytes + MULTIPART_OVERHEAD_BYTES))
+        .with_state(AttachmentState { db, storage, max_bytes })
+}
+
+const ATTACHMENT_COLUMNS: &str = "id, task_id, uploaded_by, filename, content_type, size_bytes, checksum_sha256, created_at";
+
+async fn list_attachments(
+    ctx: Ctx,
+    State(state): State<AttachmentState>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&state.db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let attachments: Vec<Attachment> = sqlx::query_as(&format!(
+        "SELECT {ATTACHMENT_COLUMNS} FROM task_attachments WHERE task_id = $1 ORDER BY created_at, id"
+    ))
+    .bind(task_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "attachments": attachments } })))
+}
+
+/// Stores the multipart field named `file`.
+async fn upload_attachment(
+    ctx: Ctx,
+    State(state): State<AttachmentState>,
+    Path(task_id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Json<Value>> {
+    require_task_role(&state.db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let filename = sanitize_filename(field.file_name().unwrap_or_default());
+        let content_type = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if data.len() + chunk.len() > state.max_bytes {
+                return Err(Error::AttachmentTooLarge);
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        upload = Some((filename, content_type, data));
+        break;
+    }
+    let (filename, content_type, data) = upload.ok_or(Error::AttachmentMissing)?;
+
+    let checksum = hex::encode(Sha256::digest(&data));
+    let storage_key = format!("tasks/{task_id}/{}", generate_token());
+
+    state.storage.put(&storage_key, &data).await?;
+
+    let inserted = sqlx::query_as::<_, Attachment>(&format!(
+        "INSERT INTO task_attachments (task_id, uploaded_by, filename, content_type, size_bytes, checksum_sha256, storage_key) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {ATTACHMENT_COLUMNS}"
+    ))
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .bind(filename)
+    .bind(content_type)
+    .bind(data.len() as i64)
+    .bind(checksum)
+    .bind(&storage_key)
+    .fetch_one(&state.db)
+    .await;
+
+    let attachment = match inserted {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            // Without a metadata row nothing would ever clean the blob up.
+            let _ = state.storage.delete(&storage_key).await;
+            return Err(err.into());
+        }
+    };
+
+    Ok(Json(json!({ "status": "success", "data": { "attachment": attachment } })))
+}
+
+async fn download_attachment(
+    ctx: Ctx,
+    State(state): State<AttachmentState>,
+    Path((task_id, attachment_id)): Path<(i64, i64)>,
+) -> Result<Response> {
+    require_task_role(&state.db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let (filename, content_type, checksum, storage_key): (String, String, String, String) =
+        sqlx::query_as(
+            "SELECT filename, content_type, checksum_sha256, storage_key FROM task_attachments WHERE id = $1 AND task_id = $2",
+        )
+        .bind(attachment_id)
+        .bind(task_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(Error::AttachmentNotFound)?;
+
+    let data = state
+        .storage
+        .get(&storage_key)
+        .await?
+        .ok_or(Error::AttachmentNotFound)?;
+
+    let headers = [
+        (header::CONTENT_TYPE, content_type),
+        (header::CONTENT_DISPOSITION, content_disposition(&filename)),
+        (header::ETAG, format!("\"{checksum}\"")),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+    ];
+
+    Ok((StatusCode::OK, headers, data).into_response())
+}
+
+/// The uploader or a project owner may delete an attachment. The blob itself
+/// is removed by the background sweeper.
+async fn delete_attachment(
+    ctx: Ctx,
+    State(state): State<AttachmentState>,
+    Path((task_id, attachment_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    let (_, role) = require_task_role(&state.db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let uploaded_by: Option<i64> = sqlx::query_scalar(
+        "SELECT uploaded_by FROM task_attachments WHERE id = $1 AND task_id = $2",
+    )
+    .bind(attachment_id)
+    .bind(task_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or(Error::AttachmentNotFound)?;
+
+    if uploaded_by != Some(ctx.user.id) && role != ProjectRole::Owner {
+        return Err(Error::AttachmentUnauthorized);
+    }
+
+    sqlx::query("DELETE FROM task_attachments WHERE id = $1")
+        .bind(attachment_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Attachment deleted" })))
+}
+
+fn multipart_error(err: MultipartError) -> Error {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        Error::AttachmentTooLarge
+    } else {
+        Error::InvalidMultipart
+    }
+}
+
+/// An ASCII `filename` for old clients plus the exact name as RFC 5987
+/// `filename*`.
+fn content_disposition(filename: &str) -> String {
+    let ascii: String = filename
+        .chars()
+        .map(|c| if c.is_ascii() && c != '\\' { c } else { '_' })
+        .collect();
+    let encoded: String = filename
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect();
+    format!("attachment; filename=\"{ascii}\"; filename*=UTF-8''{encoded}")
+}
+
+/// Keeps the last path segment of a client-supplied name and drops characters
+/// that would break the `Content-Disposition` header.
+fn sanitize_filename(name: &str) -> String {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
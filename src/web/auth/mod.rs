@@ -1,5 +1,6 @@
 use crate::ctx::Ctx;
 use crate::web::db::Db;
+use crate::web::invitation;
 use crate::web::user::User;
 use crate::{Error, Result};
 use argon2::password_hash::SaltString;
@@ -13,10 +14,22 @@ use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+pub mod refresh_token;
+pub mod revocation;
+
 pub fn routes(db: Db) -> Router {
     Router::new()
         .route("/login", post(login))
         .route("/register", post(register))
+        .route("/token/refresh", post(refresh))
+        .with_state(db)
+}
+
+/// Session routes that require a valid access token.
+pub fn protected_routes(db: Db) -> Router {
+    Router::new()
+        .route("/logout", post(logout))
+        .route("/logout/all", post(logout_all))
         .with_state(db)
 }
 
@@ -26,7 +39,8 @@ pub async fn me(ctx: Ctx) -> Result<Json<Value>> {
         "data": {
             "user": {
                 "id": ctx.user.id,
-                "username": ctx.user.username
+                "username": ctx.user.username,
+                "role": ctx.user.role
             }
         }
     })))
@@ -39,21 +53,89 @@ async fn login(State(db): State<Db>, Json(payload): Json<AuthPayload>) -> Result
         .await?
         .ok_or(Error::LoginFail)?;
 
-    let password_verified = verify_password(&payload.password, &user.password.unwrap_or_default())?;
+    let password_verified = verify_password(&payload.password, user.password.as_deref().unwrap_or_default())?;
     if !password_verified {
         return Err(Error::LoginFail);
     }
 
-    let token = create_token(user.id)?;
+    let token = create_token(&user)?;
+    let refresh_token = refresh_token::issue(&db, user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "token": token,
+            "refresh_token": refresh_token
+        }
+    })))
+}
+
+async fn refresh(
+    State(db): State<Db>,
+    Json(payload): Json<RefreshPayload>,
+) -> Result<Json<Value>> {
+    let (user_id, refresh_token) = refresh_token::rotate(&db, &payload.refresh_token).await?;
+
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(Error::RefreshTokenInvalid)?;
+    let token = create_token(&user)?;
 
     Ok(Json(json!({
         "status": "success",
         "data": {
-            "token": token
+            "token": token,
+            "refresh_token": refresh_token
         }
     })))
 }
 
+async fn logout(
+    ctx: Ctx,
+    State(db): State<Db>,
+    payload: Option<Json<LogoutPayload>>,
+) -> Result<Json<Value>> {
+    revocation::revoke(&db, &ctx.jti, ctx.user.id, ctx.token_expires_at).await?;
+
+    if let Some(Json(LogoutPayload { refresh_token: Some(token) })) = payload {
+        let family_id: Option<String> = sqlx::query_scalar(
+            "SELECT family_id FROM refresh_tokens WHERE token_hash = $1 AND user_id = $2",
+        )
+        .bind(refresh_token::hash_token(&token))
+        .bind(ctx.user.id)
+        .fetch_optional(&db)
+        .await?;
+
+        if let Some(family_id) = family_id {
+            refresh_token::revoke_family(&db, &family_id).await?;
+        }
+    }
+
+    Ok(Json(json!({ "status": "success", "message": "Logged out" })))
+}
+
+/// Invalidates every access token issued so far by bumping the user's token
+/// generation, and revokes all of their refresh tokens.
+async fn logout_all(ctx: Ctx, State(db): State<Db>) -> Result<Json<Value>> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("UPDATE users SET token_generation = token_generation + 1 WHERE id = $1")
+        .bind(ctx.user.id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+        .bind(ctx.user.id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Logged out of all sessions" })))
+}
+
 #[derive(Debug, serde::Serialize, sqlx::FromRow)]
 struct ReturnedUser {
     id: i64,
@@ -63,14 +145,23 @@ struct ReturnedUser {
 async fn register(State(db): State<Db>, Json(payload): Json<AuthPayload>) -> Result<Json<Value>> {
     let password_hash = hash_password(&payload.password)?;
 
+    let mut tx = db.begin().await?;
+
     let user = sqlx::query_as::<_, ReturnedUser>(
         "INSERT INTO users (username, password) VALUES ($1, $2) RETURNING id, username",
     )
     .bind(&payload.username)
     .bind(&password_hash)
-    .fetch_one(&db)
+    .fetch_one(&mut *tx)
     .await?;
 
+    // Registering through an invitation link joins the project in the same step.
+    if let Some(token) = &payload.invitation_token {
+        invitation::accept(&mut tx, token, user.id, &user.username).await?;
+    }
+
+    tx.commit().await?;
+
     Ok(Json(json!({
         "status": "success",
         "data": {
@@ -83,12 +174,14 @@ async fn register(State(db): State<Db>, Json(payload): Json<AuthPayload>) -> Res
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: i64,
-    exp: usize,
+pub(crate) struct Claims {
+    pub sub: i64,
+    pub exp: usize,
+    pub jti: String,
+    pub gen: i32,
 }
 
-fn create_token(user_id: i64) -> Result<String> {
+fn create_token(user: &User) -> Result<String> {
     let secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
     let expiration = Utc::now()
         .checked_add_signed(Duration::hours(1))
@@ -96,8 +189,10 @@ fn create_token(user_id: i64) -> Result<String> {
         .timestamp();
 
     let claims = Claims {
-        sub: user_id,
+        sub: user.id,
         exp: expiration as usize,
+        jti: refresh_token::generate_token(),
+        gen: user.token_generation,
     };
 
     encode(
@@ -128,4 +223,15 @@ fn verify_password(password: &str, hash: &str) -> Result<bool> {
 struct AuthPayload {
     pub username: String,
     pub password: String,
+    pub invitation_token: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RefreshPayload {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LogoutPayload {
+    pub refresh_token: Option<String>,
 }
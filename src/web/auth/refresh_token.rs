@@ -0,0 +1,106 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgExecutor};
+
+use crate::web::db::Db;
+use crate::{Error, Result};
+
+const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+#[derive(Debug, FromRow)]
+struct RefreshTokenRow {
+    id: i64,
+    user_id: i64,
+    family_id: String,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Random, URL-safe opaque token. Only its hash is ever persisted.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Starts a new token family for a fresh login and returns the plain token.
+pub async fn issue(db: &Db, user_id: i64) -> Result<String> {
+    let family_id = generate_token();
+    let (_, token) = insert(db, user_id, &family_id).await?;
+    Ok(token)
+}
+
+/// Exchanges a refresh token for a new one in the same family.
+///
+/// Every token is single-use: presenting one that was already rotated (or
+/// revoked) is treated as theft and revokes the whole family.
+pub async fn rotate(db: &Db, token: &str) -> Result<(i64, String)> {
+    let mut tx = db.begin().await?;
+
+    let current = sqlx::query_as::<_, RefreshTokenRow>(
+        "SELECT id, user_id, family_id, expires_at, revoked_at FROM refresh_tokens WHERE token_hash = $1 FOR UPDATE",
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::RefreshTokenInvalid)?;
+
+    if current.revoked_at.is_some() {
+        revoke_family(&mut *tx, &current.family_id).await?;
+        tx.commit().await?;
+        return Err(Error::RefreshTokenReused);
+    }
+
+    if current.expires_at <= Utc::now() {
+        return Err(Error::RefreshTokenInvalid);
+    }
+
+    let (new_id, new_token) = insert(&mut *tx, current.user_id, &current.family_id).await?;
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW(), replaced_by = $1 WHERE id = $2")
+        .bind(new_id)
+        .bind(current.id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((current.user_id, new_token))
+}
+
+pub async fn revoke_family<'e>(executor: impl PgExecutor<'e>, family_id: &str) -> Result<()> {
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL")
+        .bind(family_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+async fn insert<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: i64,
+    family_id: &str,
+) -> Result<(i64, String)> {
+    let token = generate_token();
+    let expires_at = Utc::now()
+        .checked_add_signed(Duration::days(REFRESH_TOKEN_TTL_DAYS))
+        .expect("valid timestamp");
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .bind(hash_token(&token))
+    .bind(expires_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok((id, token))
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::web::db::Db;
+use crate::Result;
+
+/// How long a "not revoked" answer from Postgres is trusted. Revocations made
+/// by this process are visible immediately; those made by another instance
+/// are picked up once the cached answer expires.
+const VALID_TTL: Duration = Duration::from_secs(30);
+/// Most "not revoked" answers kept between sweeps.
+const MAX_VALID_ENTRIES: usize = 10_000;
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Cache {
+    revoked: HashMap<String, DateTime<Utc>>,
+    valid: HashMap<String, Instant>,
+}
+
+static CACHE: LazyLock<RwLock<Cache>> = LazyLock::new(|| {
+    RwLock::new(Cache {
+        revoked: HashMap::new(),
+        valid: HashMap::new(),
+    })
+});
+
+pub async fn revoke(db: &Db, jti: &str, user_id: i64, expires_at: DateTime<Utc>) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO revoked_tokens (jti, user_id, expires_at) VALUES ($1, $2, $3) ON CONFLICT (jti) DO NOTHING",
+    )
+    .bind(jti)
+    .bind(user_id)
+    .bind(expires_at)
+    .execute(db)
+    .await?;
+
+    let mut cache = CACHE.write().expect("revocation cache poisoned");
+    cache.valid.remove(jti);
+    cache.revoked.insert(jti.to_string(), expires_at);
+    Ok(())
+}
+
+pub async fn is_revoked(db: &Db, jti: &str) -> Result<bool> {
+    {
+        let cache = CACHE.read().expect("revocation cache poisoned");
+        if cache.revoked.contains_key(jti) {
+            return Ok(true);
+        }
+        if let Some(checked_at) = cache.valid.get(jti) {
+            if checked_at.elapsed() < VALID_TTL {
+                return Ok(false);
+            }
+        }
+    }
+
+    let expires_at: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT expires_at FROM revoked_tokens WHERE jti = $1")
+            .bind(jti)
+            .fetch_optional(db)
+            .await?;
+
+    let mut cache = CACHE.write().expect("revocation cache poisoned");
+    match expires_at {
+        Some(expires_at) => {
+            cache.revoked.insert(jti.to_string(), expires_at);
+            Ok(true)
+        }
+        None => {
+            if cache.valid.len() >= MAX_VALID_ENTRIES {
+                sweep(&mut cache);
+            }
+            // Still full of fresh answers: start over rather than grow.
+            if cache.valid.len() >= MAX_VALID_ENTRIES {
+                cache.valid.clear();
+            }
+            cache.valid.insert(jti.to_string(), Instant::now());
+            Ok(false)
+        }
+    }
+}
+
+/// Drops entries that can no longer matter: revoked tokens past their `exp`
+/// are rejected by signature validation anyway.
+fn sweep(cache: &mut Cache) {
+    let now = Utc::now();
+    cache.revoked.retain(|_, expires_at| *expires_at > now);
+    cache.valid.retain(|_, checked_at| checked_at.elapsed() < VALID_TTL);
+}
+
+/// Deletes revoked tokens past their `exp`, which signature validation rejects
+/// on its own. Returns how many were deleted.
+pub async fn purge_expired(db: &Db) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at < NOW()")
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Sweeps the cache and runs [`purge_expired`] periodically for the lifetime
+/// of the process.
+pub fn spawn_revocation_sweeper(db: Db) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            sweep(&mut CACHE.write().expect("revocation cache poisoned"));
+            if let Err(err) = purge_expired(&db).await {
+                eprintln!("->> {:<12} - revoked token purge failed: {err:?}", "AUTH");
+            }
+        }
+    });
+}
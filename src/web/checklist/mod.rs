@@ -0,0 +1,42 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub task_id: i64,
+    pub text: String,
+    pub checked: bool,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Checked and total checklist items of a task.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ChecklistProgress {
+    pub done: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateChecklistItemPayload {
+    pub text: String,
+    #[serde(default)]
+    pub checked: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateChecklistItemPayload {
+    pub text: Option<String>,
+    pub checked: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReorderChecklistPayload {
+    /// Every item of the task, in the new order.
+    pub item_ids: Vec<i64>,
+}
@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::checklist::{
+    ChecklistItem, CreateChecklistItemPayload, ReorderChecklistPayload, UpdateChecklistItemPayload,
+};
+use crate::web::db::Db;
+use crate::web::member::{require_task_role, ProjectRole};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route(
+            "/tasks/{id}/checklist",
+            get(list_items).post(create_item),
+        )
+        .route("/tasks/{id}/checklist/order", put(reorder_items))
+        .route(
+            "/tasks/{id}/checklist/{item_id}",
+            put(update_item).delete(delete_item),
+        )
+        .with_state(db)
+}
+
+async fn list_items(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let items: Vec<ChecklistItem> = sqlx::query_as(
+        "SELECT * FROM task_checklist_items WHERE task_id = $1 ORDER BY position, id",
+    )
+    .bind(task_id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "items": items } })))
+}
+
+/// New items go to the end of the checklist.
+async fn create_item(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<CreateChecklistItemPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let text = payload.text.trim();
+    if text.is_empty() {
+        return Err(Error::ChecklistItemEmpty);
+    }
+
+    let item = sqlx::query_as::<_, ChecklistItem>(
+        "INSERT INTO task_checklist_items (task_id, text, checked, position) \
+         VALUES ($1, $2, $3, (SELECT COALESCE(MAX(position) + 1, 0) FROM task_checklist_items WHERE task_id = $1)) \
+         RETURNING *",
+    )
+    .bind(task_id)
+    .bind(text)
+    .bind(payload.checked)
+    .fetch_one(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "item": item } })))
+}
+
+/// Edits an item's text and/or toggles it.
+async fn update_item(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, item_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateChecklistItemPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let text = payload.text.as_deref().map(str::trim);
+    if text == Some("") {
+        return Err(Error::ChecklistItemEmpty);
+    }
+
+    let item = sqlx::query_as::<_, ChecklistItem>(
+        "UPDATE task_checklist_items SET text = COALESCE($1, text), checked = COALESCE($2, checked) WHERE id = $3 AND task_id = $4 RETURNING *",
+    )
+    .bind(text)
+    .bind(payload.checked)
+    .bind(item_id)
+    .bind(task_id)
+    .fetch_optional(&db)
+    .await?
+    .ok_or(Error::ChecklistItemNotFound)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "item": item } })))
+}
+
+/// Rewrites the order of the whole checklist.
+async fn reorder_items(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<ReorderChecklistPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let mut tx = db.begin().await?;
+
+    let current_ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM task_checklist_items WHERE task_id = $1 ORDER BY id FOR UPDATE",
+    )
+    .bind(task_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut requested_ids = payload.item_ids.clone();
+    requested_ids.sort_unstable();
+    if requested_ids != current_ids {
+        return Err(Error::InvalidChecklistOrder);
+    }
+
+    sqlx::query(
+        "UPDATE task_checklist_items ci SET position = o.ord - 1 \
+         FROM UNNEST($2::BIGINT[]) WITH ORDINALITY AS o(id, ord) \
+         WHERE ci.id = o.id AND ci.task_id = $1",
+    )
+    .bind(task_id)
+    .bind(&payload.item_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    let items: Vec<ChecklistItem> = sqlx::query_as(
+        "SELECT * FROM task_checklist_items WHERE task_id = $1 ORDER BY position, id",
+    )
+    .bind(task_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "items": items } })))
+}
+
+async fn delete_item(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, item_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let rows_affected = sqlx::query("DELETE FROM task_checklist_items WHERE id = $1 AND task_id = $2")
+        .bind(item_id)
+        .bind(task_id)
+        .execute(&db)
+        .await?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::ChecklistItemNotFound);
+    }
+
+    Ok(Json(json!({ "status": "success", "message": "Checklist item deleted" })))
+}
@@ -0,0 +1,44 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct Comment {
+    pub id: i64,
+    pub task_id: i64,
+    /// `None` once the author's account has been deleted.
+    pub user_id: Option<i64>,
+    pub author_username: Option<String>,
+    pub parent_comment_id: Option<i64>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+/// A top-level comment with its replies. Replies cannot be replied to.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct CommentThread {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub replies: Vec<Comment>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCommentPayload {
+    pub body: String,
+    pub parent_comment_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateCommentPayload {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentListQueryParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
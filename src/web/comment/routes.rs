@@ -0,0 +1,212 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::comment::{
+    Comment, CommentListQueryParams, CommentThread, CreateCommentPayload, UpdateCommentPayload,
+};
+use crate::web::db::Db;
+use crate::web::member::{require_task_role, ProjectRole};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route(
+            "/tasks/{id}/comments",
+            get(list_comments).post(create_comment),
+        )
+        .route(
+            "/tasks/{id}/comments/{comment_id}",
+            put(update_comment).delete(delete_comment),
+        )
+        .with_state(db)
+}
+
+const COMMENT_SELECT: &str = "SELECT c.id, c.task_id, c.user_id, u.username AS author_username, c.parent_comment_id, c.body, c.created_at, c.edited_at FROM task_comments c LEFT JOIN users u ON u.id = c.user_id";
+
+async fn create_comment(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<CreateCommentPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    if payload.body.trim().is_empty() {
+        return Err(Error::CommentEmpty);
+    }
+
+    if let Some(parent_id) = payload.parent_comment_id {
+        let is_top_level_on_task: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM task_comments WHERE id = $1 AND task_id = $2 AND parent_comment_id IS NULL)",
+        )
+        .bind(parent_id)
+        .bind(task_id)
+        .fetch_one(&db)
+        .await?;
+
+        if !is_top_level_on_task {
+            return Err(Error::InvalidParentComment);
+        }
+    }
+
+    let comment_id: i64 = sqlx::query_scalar(
+        "INSERT INTO task_comments (task_id, user_id, parent_comment_id, body) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .bind(payload.parent_comment_id)
+    .bind(payload.body)
+    .fetch_one(&db)
+    .await?;
+
+    let comment = fetch_comment(&db, task_id, comment_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "comment": comment } })))
+}
+
+/// Paginates top-level comments, oldest first, each with all of its replies.
+async fn list_comments(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Query(params): Query<CommentListQueryParams>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let page = params.page.unwrap_or(1);
+    let limit = params.limit.unwrap_or(20);
+    if page == 0 || limit == 0 {
+        return Err(Error::InvalidPagination);
+    }
+    let offset = (page as i64 - 1).saturating_mul(limit as i64);
+
+    let comments: Vec<Comment> = sqlx::query_as(&format!(
+        "{COMMENT_SELECT} WHERE c.task_id = $1 AND c.parent_comment_id IS NULL ORDER BY c.created_at, c.id LIMIT $2 OFFSET $3"
+    ))
+    .bind(task_id)
+    .bind(limit as i64)
+    .bind(offset)
+    .fetch_all(&db)
+    .await?;
+
+    let total_comments: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM task_comments WHERE task_id = $1 AND parent_comment_id IS NULL",
+    )
+    .bind(task_id)
+    .fetch_one(&db)
+    .await?;
+
+    let parent_ids: Vec<i64> = comments.iter().map(|comment| comment.id).collect();
+    let replies: Vec<Comment> = sqlx::query_as(&format!(
+        "{COMMENT_SELECT} WHERE c.parent_comment_id = ANY($1) ORDER BY c.created_at, c.id"
+    ))
+    .bind(&parent_ids)
+    .fetch_all(&db)
+    .await?;
+
+    let mut replies_by_parent: HashMap<i64, Vec<Comment>> = HashMap::new();
+    for reply in replies {
+        if let Some(parent_id) = reply.parent_comment_id {
+            replies_by_parent.entry(parent_id).or_default().push(reply);
+        }
+    }
+
+    let threads: Vec<CommentThread> = comments
+        .into_iter()
+        .map(|comment| CommentThread {
+            replies: replies_by_parent.remove(&comment.id).unwrap_or_default(),
+            comment,
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "comments": threads,
+            "pagination": {
+                "total": total_comments,
+                "page": page,
+                "limit": limit,
+                "total_pages": (total_comments as f64 / limit as f64).ceil() as u32
+            }
+        }
+    })))
+}
+
+/// Only the author may edit a comment.
+async fn update_comment(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, comment_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateCommentPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    if payload.body.trim().is_empty() {
+        return Err(Error::CommentEmpty);
+    }
+
+    let author_id = comment_author(&db, task_id, comment_id).await?;
+    if author_id != Some(ctx.user.id) {
+        return Err(Error::CommentUnauthorized);
+    }
+
+    sqlx::query("UPDATE task_comments SET body = $1, edited_at = NOW() WHERE id = $2")
+        .bind(payload.body)
+        .bind(comment_id)
+        .execute(&db)
+        .await?;
+
+    let comment = fetch_comment(&db, task_id, comment_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "comment": comment } })))
+}
+
+/// The author or a project owner may delete a comment. Replies go with it.
+async fn delete_comment(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, comment_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    let (_, role) = require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let author_id = comment_author(&db, task_id, comment_id).await?;
+    if author_id != Some(ctx.user.id) && role != ProjectRole::Owner {
+        return Err(Error::CommentUnauthorized);
+    }
+
+    sqlx::query("DELETE FROM task_comments WHERE id = $1")
+        .bind(comment_id)
+        .execute(&db)
+        .await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Comment deleted" })))
+}
+
+async fn fetch_comment(db: &Db, task_id: i64, comment_id: i64) -> Result<Comment> {
+    sqlx::query_as::<_, Comment>(&format!(
+        "{COMMENT_SELECT} WHERE c.id = $1 AND c.task_id = $2"
+    ))
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(Error::CommentNotFound)
+}
+
+async fn comment_author(db: &Db, task_id: i64, comment_id: i64) -> Result<Option<i64>> {
+    sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT user_id FROM task_comments WHERE id = $1 AND task_id = $2",
+    )
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(Error::CommentNotFound)
+}
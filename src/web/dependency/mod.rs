@@ -0,0 +1,71 @@
+pub mod routes;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use utoipa::ToSchema;
+
+use crate::{Error, Result};
+
+/// The other end of a dependency edge, trimmed down to what a client needs to
+/// render it.
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct DependencyTask {
+    pub id: i64,
+    pub project_id: i64,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddDependencyPayload {
+    pub blocked_by_task_id: i64,
+}
+
+/// Fails when making `task_id` wait on `blocked_by_task_id` would close a loop,
+/// i.e. when the blocker already (transitively) waits on the task.
+///
+/// Edges may span projects, so edits take a global transaction-level lock
+/// rather than a per-project one.
+pub(crate) async fn ensure_acyclic(
+    conn: &mut PgConnection,
+    task_id: i64,
+    blocked_by_task_id: i64,
+) -> Result<()> {
+    if task_id == blocked_by_task_id {
+        return Err(Error::TaskDependencyCycle);
+    }
+
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('task_dependencies'))")
+        .execute(&mut *conn)
+        .await?;
+
+    let creates_cycle: bool = sqlx::query_scalar(
+        "WITH RECURSIVE upstream AS ( \
+            SELECT blocked_by_task_id AS id FROM task_dependencies WHERE task_id = $1 \
+            UNION SELECT d.blocked_by_task_id FROM task_dependencies d JOIN upstream u ON d.task_id = u.id \
+        ) SELECT EXISTS (SELECT 1 FROM upstream WHERE id = $2)",
+    )
+    .bind(blocked_by_task_id)
+    .bind(task_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if creates_cycle {
+        return Err(Error::TaskDependencyCycle);
+    }
+    Ok(())
+}
+
+/// Ids of the tasks that still block `task_id`, i.e. are not in a `Done`
+/// status.
+pub(crate) async fn open_blockers(conn: &mut PgConnection, task_id: i64) -> Result<Vec<i64>> {
+    let blockers = sqlx::query_scalar(
+        "SELECT b.id FROM task_dependencies d JOIN tasks b ON b.id = d.blocked_by_task_id \
+         JOIN workflow_statuses ws ON ws.project_id = b.project_id AND ws.key = b.status \
+         WHERE d.task_id = $1 AND ws.category <> 'done' ORDER BY b.id",
+    )
+    .bind(task_id)
+    .fetch_all(conn)
+    .await?;
+    Ok(blockers)
+}
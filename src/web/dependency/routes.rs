@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::db::Db;
+use crate::web::dependency::{ensure_acyclic, AddDependencyPayload, DependencyTask};
+use crate::web::member::{require_task_role, ProjectRole};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route(
+            "/tasks/{id}/dependencies",
+            get(list_dependencies).post(add_dependency),
+        )
+        .route(
+            "/tasks/{id}/dependencies/{blocked_by_task_id}",
+            delete(remove_dependency),
+        )
+        .with_state(db)
+}
+
+/// Lists both directions: what this task waits on and what waits on it. Tasks
+/// in projects the caller cannot see are left out.
+async fn list_dependencies(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let blocked_by: Vec<DependencyTask> = sqlx::query_as(
+        "SELECT t.id, t.project_id, t.title, t.status FROM task_dependencies d JOIN tasks t ON t.id = d.blocked_by_task_id JOIN project_members pm ON pm.project_id = t.project_id AND pm.user_id = $2 WHERE d.task_id = $1 ORDER BY t.id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .fetch_all(&db)
+    .await?;
+
+    let blocking: Vec<DependencyTask> = sqlx::query_as(
+        "SELECT t.id, t.project_id, t.title, t.status FROM task_dependencies d JOIN tasks t ON t.id = d.task_id JOIN project_members pm ON pm.project_id = t.project_id AND pm.user_id = $2 WHERE d.blocked_by_task_id = $1 ORDER BY t.id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "blocked_by": blocked_by, "blocking": blocking }
+    })))
+}
+
+async fn add_dependency(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<AddDependencyPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+    require_task_role(&db, payload.blocked_by_task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let mut tx = db.begin().await?;
+
+    ensure_acyclic(&mut tx, task_id, payload.blocked_by_task_id).await?;
+
+    sqlx::query(
+        "INSERT INTO task_dependencies (task_id, blocked_by_task_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(task_id)
+    .bind(payload.blocked_by_task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "dependency": {
+                "task_id": task_id,
+                "blocked_by_task_id": payload.blocked_by_task_id
+            }
+        }
+    })))
+}
+
+async fn remove_dependency(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, blocked_by_task_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let rows_affected = sqlx::query(
+        "DELETE FROM task_dependencies WHERE task_id = $1 AND blocked_by_task_id = $2",
+    )
+    .bind(task_id)
+    .bind(blocked_by_task_id)
+    .execute(&db)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::DependencyNotFound);
+    }
+
+    Ok(Json(json!({ "status": "success", "message": "Dependency removed" })))
+}
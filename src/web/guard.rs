@@ -0,0 +1,41 @@
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
+
+use crate::ctx::Ctx;
+use crate::Error;
+
+/// Extractor for handlers restricted to administrators.
+pub struct AdminCtx(pub Ctx);
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminCtx {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = Ctx::from_request_parts(parts, state).await?;
+        if !ctx.is_admin() {
+            return Err(Error::AdminRequired);
+        }
+        Ok(AdminCtx(ctx))
+    }
+}
+
+/// Extractor for `/users/{id}` handlers that a user may perform on their own
+/// account, or an administrator on anyone's. Yields the target user id.
+pub struct SelfOrAdmin(pub i64);
+
+impl<S: Send + Sync> FromRequestParts<S> for SelfOrAdmin {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ctx = Ctx::from_request_parts(parts, state).await?;
+        // A non-numeric id cannot name an existing user.
+        let Path(user_id) = Path::<i64>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        if ctx.user.id != user_id && !ctx.is_admin() {
+            return Err(Error::UserUnauthorized);
+        }
+        Ok(SelfOrAdmin(user_id))
+    }
+}
@@ -0,0 +1,101 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, Type};
+use utoipa::ToSchema;
+
+use crate::web::auth::refresh_token::hash_token;
+use crate::web::member::ProjectRole;
+use crate::{Error, Result};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "invitation_status", rename_all = "snake_case")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Revoked,
+}
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct Invitation {
+    pub id: i64,
+    pub project_id: i64,
+    pub invited_by: Option<i64>,
+    pub invitee_username: Option<String>,
+    pub role: ProjectRole,
+    pub status: InvitationStatus,
+    pub expires_at: DateTime<Utc>,
+    pub responded_by: Option<i64>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateInvitationPayload {
+    /// Restricts the invitation to one (possibly not yet registered) username.
+    /// Without it, whoever holds the token may accept.
+    pub invitee_username: Option<String>,
+    pub role: ProjectRole,
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InvitationTokenPayload {
+    pub token: String,
+}
+
+pub(crate) const INVITATION_COLUMNS: &str = "id, project_id, invited_by, invitee_username, role, status, expires_at, responded_by, responded_at, created_at";
+
+/// Consumes a pending invitation on behalf of `user_id` and grants the
+/// membership. Never downgrades an existing member's role.
+pub async fn accept(
+    conn: &mut PgConnection,
+    token: &str,
+    user_id: i64,
+    username: &str,
+) -> Result<Invitation> {
+    let invitation = respond(&mut *conn, token, user_id, username, InvitationStatus::Accepted).await?;
+
+    sqlx::query(
+        "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, $3) ON CONFLICT (project_id, user_id) DO UPDATE SET role = GREATEST(project_members.role, EXCLUDED.role)",
+    )
+    .bind(invitation.project_id)
+    .bind(user_id)
+    .bind(invitation.role)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(invitation)
+}
+
+pub async fn decline(
+    conn: &mut PgConnection,
+    token: &str,
+    user_id: i64,
+    username: &str,
+) -> Result<Invitation> {
+    respond(conn, token, user_id, username, InvitationStatus::Declined).await
+}
+
+/// Atomically moves a pending, unexpired invitation addressed to `username`
+/// (or to anyone) into its final state, which makes the token single-use.
+async fn respond(
+    conn: &mut PgConnection,
+    token: &str,
+    user_id: i64,
+    username: &str,
+    status: InvitationStatus,
+) -> Result<Invitation> {
+    sqlx::query_as::<_, Invitation>(&format!(
+        "UPDATE project_invitations SET status = $1, responded_by = $2, responded_at = NOW() WHERE token_hash = $3 AND status = 'pending' AND expires_at > NOW() AND (invitee_username IS NULL OR invitee_username = $4) RETURNING {INVITATION_COLUMNS}"
+    ))
+    .bind(status)
+    .bind(user_id)
+    .bind(hash_token(token))
+    .bind(username)
+    .fetch_optional(conn)
+    .await?
+    .ok_or(Error::InvitationInvalid)
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, post},
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::auth::refresh_token::{generate_token, hash_token};
+use crate::web::db::Db;
+use crate::web::invitation::{
+    self, CreateInvitationPayload, Invitation, InvitationTokenPayload, INVITATION_COLUMNS,
+};
+use crate::web::member::{require_project_role, ProjectRole};
+
+const DEFAULT_INVITATION_TTL_HOURS: i64 = 7 * 24;
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route(
+            "/projects/{id}/invitations",
+            post(create_invitation).get(list_invitations),
+        )
+        .route(
+            "/projects/{id}/invitations/{invitation_id}",
+            delete(revoke_invitation),
+        )
+        .route("/invitations/accept", post(accept_invitation))
+        .route("/invitations/decline", post(decline_invitation))
+        .with_state(db)
+}
+
+async fn create_invitation(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Json(payload): Json<CreateInvitationPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let ttl_hours = payload
+        .expires_in_hours
+        .unwrap_or(DEFAULT_INVITATION_TTL_HOURS);
+    let expires_at = Duration::try_hours(ttl_hours)
+        .filter(|ttl| *ttl > Duration::zero())
+        .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+        .ok_or(Error::InvitationExpiryInvalid)?;
+
+    let token = generate_token();
+
+    let invitation = sqlx::query_as::<_, Invitation>(&format!(
+        "INSERT INTO project_invitations (project_id, invited_by, invitee_username, role, token_hash, expires_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING {INVITATION_COLUMNS}"
+    ))
+    .bind(project_id)
+    .bind(ctx.user.id)
+    .bind(payload.invitee_username)
+    .bind(payload.role)
+    .bind(hash_token(&token))
+    .bind(expires_at)
+    .fetch_one(&db)
+    .await?;
+
+    // The plain token is only ever returned here; it must be passed on to the invitee.
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "invitation": invitation, "token": token }
+    })))
+}
+
+async fn list_invitations(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let invitations: Vec<Invitation> = sqlx::query_as(&format!(
+        "SELECT {INVITATION_COLUMNS} FROM project_invitations WHERE project_id = $1 ORDER BY created_at DESC"
+    ))
+    .bind(project_id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "invitations": invitations } })))
+}
+
+async fn revoke_invitation(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, invitation_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let invitation = sqlx::query_as::<_, Invitation>(&format!(
+        "UPDATE project_invitations SET status = 'revoked' WHERE id = $1 AND project_id = $2 AND status = 'pending' RETURNING {INVITATION_COLUMNS}"
+    ))
+    .bind(invitation_id)
+    .bind(project_id)
+    .fetch_optional(&db)
+    .await?
+    .ok_or(Error::InvitationNotFound)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "invitation": invitation } })))
+}
+
+async fn accept_invitation(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Json(payload): Json<InvitationTokenPayload>,
+) -> Result<Json<Value>> {
+    let mut tx = db.begin().await?;
+    let invitation =
+        invitation::accept(&mut tx, &payload.token, ctx.user.id, &ctx.user.username).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "invitation": invitation } })))
+}
+
+async fn decline_invitation(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Json(payload): Json<InvitationTokenPayload>,
+) -> Result<Json<Value>> {
+    let mut conn = db.acquire().await?;
+    let invitation =
+        invitation::decline(&mut conn, &payload.token, ctx.user.id, &ctx.user.username).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "invitation": invitation } })))
+}
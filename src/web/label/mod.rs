@@ -0,0 +1,90 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use utoipa::ToSchema;
+
+use crate::{Error, Result};
+
+pub const DEFAULT_LABEL_COLOR: &str = "#6b7280";
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct Label {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The compact form embedded in task responses.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TaskLabel {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateLabelPayload {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateLabelPayload {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Colors are stored as `#rrggbb` hex strings.
+pub fn validate_color(color: &str) -> Result<()> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(Error::InvalidLabelColor);
+    }
+    Ok(())
+}
+
+/// Replaces a task's labels, checking every label belongs to the task's
+/// project.
+pub(crate) async fn set_task_labels(
+    conn: &mut PgConnection,
+    task_id: i64,
+    project_id: i64,
+    label_ids: &[i64],
+) -> Result<()> {
+    let mut label_ids = label_ids.to_vec();
+    label_ids.sort_unstable();
+    label_ids.dedup();
+
+    let project_labels: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM labels WHERE project_id = $1 AND id = ANY($2)")
+            .bind(project_id)
+            .bind(&label_ids)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    if project_labels != label_ids.len() as i64 {
+        return Err(Error::LabelNotInProject);
+    }
+
+    sqlx::query("DELETE FROM task_labels WHERE task_id = $1 AND label_id <> ALL($2)")
+        .bind(task_id)
+        .bind(&label_ids)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO task_labels (task_id, label_id) SELECT $1, UNNEST($2::BIGINT[]) ON CONFLICT DO NOTHING",
+    )
+    .bind(task_id)
+    .bind(&label_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::db::Db;
+use crate::web::label::{
+    validate_color, CreateLabelPayload, Label, UpdateLabelPayload, DEFAULT_LABEL_COLOR,
+};
+use crate::web::member::{require_project_role, ProjectRole};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route("/projects/{id}/labels", get(list_labels).post(create_label))
+        .route(
+            "/projects/{id}/labels/{label_id}",
+            put(update_label).delete(delete_label),
+        )
+        .with_state(db)
+}
+
+async fn list_labels(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let labels: Vec<Label> =
+        sqlx::query_as("SELECT * FROM labels WHERE project_id = $1 ORDER BY name")
+            .bind(project_id)
+            .fetch_all(&db)
+            .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "labels": labels } })))
+}
+
+async fn create_label(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Json(payload): Json<CreateLabelPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(Error::LabelNameEmpty);
+    }
+    let color = payload.color.as_deref().unwrap_or(DEFAULT_LABEL_COLOR);
+    validate_color(color)?;
+
+    let label = sqlx::query_as::<_, Label>(
+        "INSERT INTO labels (project_id, name, color) VALUES ($1, $2, $3) ON CONFLICT (project_id, name) DO NOTHING RETURNING *",
+    )
+    .bind(project_id)
+    .bind(name)
+    .bind(color)
+    .fetch_optional(&db)
+    .await?
+    .ok_or(Error::LabelAlreadyExists)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "label": label } })))
+}
+
+async fn update_label(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, label_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateLabelPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let name = payload.name.as_deref().map(str::trim);
+    if name == Some("") {
+        return Err(Error::LabelNameEmpty);
+    }
+    if let Some(color) = &payload.color {
+        validate_color(color)?;
+    }
+
+    let label = sqlx::query_as::<_, Label>(
+        "UPDATE labels SET name = COALESCE($1, name), color = COALESCE($2, color) WHERE id = $3 AND project_id = $4 RETURNING *",
+    )
+    .bind(name)
+    .bind(payload.color)
+    .bind(label_id)
+    .bind(project_id)
+    .fetch_optional(&db)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => Error::LabelAlreadyExists,
+        err => Error::SqlxError(err),
+    })?
+    .ok_or(Error::LabelNotFound)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "label": label } })))
+}
+
+async fn delete_label(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, label_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let rows_affected = sqlx::query("DELETE FROM labels WHERE id = $1 AND project_id = $2")
+        .bind(label_id)
+        .bind(project_id)
+        .execute(&db)
+        .await?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::LabelNotFound);
+    }
+
+    Ok(Json(json!({ "status": "success", "message": "Label deleted" })))
+}
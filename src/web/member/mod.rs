@@ -0,0 +1,84 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, Type};
+use utoipa::ToSchema;
+
+use crate::{Error, Result};
+
+/// Declared from least to most privileged, matching the `project_role`
+/// Postgres enum so comparisons behave the same in Rust and SQL.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "project_role", rename_all = "snake_case")]
+pub enum ProjectRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct ProjectMember {
+    pub project_id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub role: ProjectRole,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddMemberPayload {
+    pub user_id: i64,
+    pub role: ProjectRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMemberPayload {
+    pub role: ProjectRole,
+}
+
+/// Checks that `user_id` holds at least `min_role` in the project and returns
+/// their actual role.
+pub async fn require_project_role<'e>(
+    executor: impl PgExecutor<'e>,
+    project_id: i64,
+    user_id: i64,
+    min_role: ProjectRole,
+) -> Result<ProjectRole> {
+    let (_, role) = sqlx::query_as::<_, (i64, Option<ProjectRole>)>(
+        "SELECT p.id, pm.role FROM projects p LEFT JOIN project_members pm ON pm.project_id = p.id AND pm.user_id = $2 WHERE p.id = $1",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(Error::ProjectNotFound)?;
+
+    match role {
+        Some(role) if role >= min_role => Ok(role),
+        _ => Err(Error::ProjectUnauthorized),
+    }
+}
+
+/// Same as [`require_project_role`] but resolved through a task. Returns the
+/// task's project id and the caller's role in it.
+pub async fn require_task_role<'e>(
+    executor: impl PgExecutor<'e>,
+    task_id: i64,
+    user_id: i64,
+    min_role: ProjectRole,
+) -> Result<(i64, ProjectRole)> {
+    let (project_id, role) = sqlx::query_as::<_, (i64, Option<ProjectRole>)>(
+        "SELECT t.project_id, pm.role FROM tasks t LEFT JOIN project_members pm ON pm.project_id = t.project_id AND pm.user_id = $2 WHERE t.id = $1",
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(Error::TaskNotFound)?;
+
+    match role {
+        Some(role) if role >= min_role => Ok((project_id, role)),
+        _ => Err(Error::TaskUnauthorized),
+    }
+}
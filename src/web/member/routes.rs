@@ -0,0 +1,188 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::db::Db;
+use crate::web::member::{
+    require_project_role, AddMemberPayload, ProjectMember, ProjectRole, UpdateMemberPayload,
+};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route("/projects/{id}/members", get(list_members).post(add_member))
+        .route(
+            "/projects/{id}/members/{user_id}",
+            put(update_member).delete(remove_member),
+        )
+        .with_state(db)
+}
+
+const MEMBER_SELECT: &str = "SELECT pm.project_id, pm.user_id, u.username, pm.role, pm.created_at FROM project_members pm JOIN users u ON u.id = pm.user_id";
+
+async fn list_members(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let members: Vec<ProjectMember> = sqlx::query_as(&format!(
+        "{MEMBER_SELECT} WHERE pm.project_id = $1 ORDER BY pm.role DESC, u.username"
+    ))
+    .bind(project_id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "members": members } })))
+}
+
+async fn add_member(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Json(payload): Json<AddMemberPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let user_exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+        .bind(payload.user_id)
+        .fetch_one(&db)
+        .await?;
+    if !user_exists {
+        return Err(Error::UserNotFound);
+    }
+
+    let rows_affected = sqlx::query(
+        "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, $3) ON CONFLICT (project_id, user_id) DO NOTHING",
+    )
+    .bind(project_id)
+    .bind(payload.user_id)
+    .bind(payload.role)
+    .execute(&db)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::MemberAlreadyExists);
+    }
+
+    let member = fetch_member(&db, project_id, payload.user_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "member": member } })))
+}
+
+async fn update_member(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, user_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateMemberPayload>,
+) -> Result<Json<Value>> {
+    let mut tx = db.begin().await?;
+
+    require_project_role(&mut *tx, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    if payload.role != ProjectRole::Owner {
+        ensure_other_owner(&mut tx, project_id, user_id).await?;
+    }
+
+    let rows_affected = sqlx::query(
+        "UPDATE project_members SET role = $1 WHERE project_id = $2 AND user_id = $3",
+    )
+    .bind(payload.role)
+    .bind(project_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::MemberNotFound);
+    }
+
+    tx.commit().await?;
+
+    let member = fetch_member(&db, project_id, user_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "member": member } })))
+}
+
+/// Owners can remove anyone; any member can remove themselves (leave).
+async fn remove_member(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, user_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    let mut tx = db.begin().await?;
+
+    let min_role = if user_id == ctx.user.id {
+        ProjectRole::Viewer
+    } else {
+        ProjectRole::Owner
+    };
+    require_project_role(&mut *tx, project_id, ctx.user.id, min_role).await?;
+
+    ensure_other_owner(&mut tx, project_id, user_id).await?;
+
+    let rows_affected = sqlx::query("DELETE FROM project_members WHERE project_id = $1 AND user_id = $2")
+        .bind(project_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::MemberNotFound);
+    }
+
+    // Former members can no longer be responsible for the project's tasks.
+    sqlx::query(
+        "DELETE FROM task_assignees ta USING tasks t WHERE ta.task_id = t.id AND t.project_id = $1 AND ta.user_id = $2",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Member removed" })))
+}
+
+async fn fetch_member(db: &Db, project_id: i64, user_id: i64) -> Result<ProjectMember> {
+    sqlx::query_as::<_, ProjectMember>(&format!(
+        "{MEMBER_SELECT} WHERE pm.project_id = $1 AND pm.user_id = $2"
+    ))
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(Error::MemberNotFound)
+}
+
+/// Fails if demoting or removing `user_id` would leave the project without an
+/// owner. Locks the project row so concurrent changes are serialized.
+async fn ensure_other_owner(conn: &mut PgConnection, project_id: i64, user_id: i64) -> Result<()> {
+    sqlx::query("SELECT id FROM projects WHERE id = $1 FOR UPDATE")
+        .bind(project_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let other_owners: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM project_members WHERE project_id = $1 AND user_id <> $2 AND role = 'owner'",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if other_owners == 0 {
+        return Err(Error::LastProjectOwner);
+    }
+    Ok(())
+}
@@ -1,6 +1,19 @@
 pub mod db;
 pub mod mw_auth;
+pub mod guard;
 pub mod auth;
 pub mod user;
 pub mod project;
-pub mod task;
\ No newline at end of file
+pub mod member;
+pub mod invitation;
+pub mod task;
+pub mod dependency;
+pub mod comment;
+pub mod checklist;
+pub mod time_entry;
+pub mod attachment;
+pub mod activity;
+pub mod template;
+pub mod reminder;
+pub mod label;
+pub mod workflow;
\ No newline at end of file
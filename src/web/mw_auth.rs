@@ -1,4 +1,5 @@
 use crate::ctx::Ctx;
+use crate::web::auth::{revocation, Claims};
 use crate::web::db::Db;
 use crate::web::user::User;
 use crate::{Error, Result};
@@ -7,26 +8,23 @@ use axum::http::request::Parts;
 use axum::http::Request;
 use axum::middleware::Next;
 use axum::response::Response;
+use chrono::DateTime;
 use jsonwebtoken::{decode, DecodingKey, Validation};
-use serde::{Deserialize, Serialize};
-use std::future::Future;
 
 impl<S: Send + Sync> FromRequestParts<S> for Ctx {
     type Rejection = Error;
 
-    fn from_request_parts<'a, 'b>(
-        parts: &'a mut Parts,
-        _state: &'b S,
-    ) -> impl Future<Output = std::result::Result<Self, Self::Rejection>> + Send {
-        async move {
-            println!("->> {:<12} - Ctx::from_request_parts", "EXTRACTOR");
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        println!("->> {:<12} - Ctx::from_request_parts", "EXTRACTOR");
 
-            parts
-                .extensions
-                .get::<Ctx>()
-                .cloned()
-                .ok_or(Error::AuthFail)
-        }
+        parts
+            .extensions
+            .get::<Ctx>()
+            .cloned()
+            .ok_or(Error::AuthFail)
     }
 }
 
@@ -50,20 +48,30 @@ pub async fn mw_auth(
     let token_data = decode::<Claims>(token, &decoding_key, &validation)
         .map_err(|_| Error::AuthFail)?;
 
+    let claims = token_data.claims;
+
+    if revocation::is_revoked(&db, &claims.jti).await? {
+        return Err(Error::AuthFail);
+    }
+
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(token_data.claims.sub)
+        .bind(claims.sub)
         .fetch_optional(&db)
         .await?
         .ok_or(Error::AuthFail)?;
 
-    let ctx = Ctx { user };
+    // Tokens minted before the last "log out everywhere" are stale.
+    if claims.gen != user.token_generation {
+        return Err(Error::AuthFail);
+    }
+
+    let token_expires_at = DateTime::from_timestamp(claims.exp as i64, 0).ok_or(Error::AuthFail)?;
+    let ctx = Ctx {
+        user,
+        jti: claims.jti,
+        token_expires_at,
+    };
     req.extensions_mut().insert(ctx);
 
     Ok(next.run(req).await)
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: i64,
-    exp: usize,
-}
\ No newline at end of file
@@ -2,9 +2,12 @@ pub mod routes;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection, PgExecutor};
 use utoipa::ToSchema;
 
+use crate::web::workflow::StatusCategory;
+use crate::{Error, Result};
+
 #[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
 pub struct Project {
     pub id: i64,
@@ -13,6 +16,8 @@ pub struct Project {
     pub description: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Sum of the finished time entries of all the project's tasks.
+    pub time_spent_seconds: i64,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -27,8 +32,59 @@ pub struct UpdateProjectPayload {
     pub description: Option<String>,
 }
 
+/// Planned and logged work of the project's tasks in one workflow status.
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct StatusSummary {
+    pub status: String,
+    pub name: String,
+    pub category: StatusCategory,
+    pub task_count: i64,
+    pub estimate_minutes: i64,
+    pub story_points: i64,
+    pub logged_seconds: i64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ProjectListQueryParams {
     pub page: Option<u32>,
     pub limit: Option<u32>,
-}
\ No newline at end of file
+}
+
+/// Select list producing a [`Project`] row, computed columns included. The
+/// `projects` table must be aliased as `p`.
+pub(crate) const PROJECT_COLUMNS: &str = "p.*, \
+    (SELECT COALESCE(SUM(te.duration_seconds), 0)::BIGINT FROM time_entries te JOIN tasks t ON t.id = te.task_id \
+        WHERE t.project_id = p.id) AS time_spent_seconds";
+
+pub(crate) async fn fetch_project<'e>(executor: impl PgExecutor<'e>, project_id: i64) -> Result<Project> {
+    sqlx::query_as::<_, Project>(&format!("SELECT {PROJECT_COLUMNS} FROM projects p WHERE p.id = $1"))
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or(Error::ProjectNotFound)
+}
+
+/// Creates a project owned by `user_id`, without a workflow.
+pub(crate) async fn insert_project(
+    conn: &mut PgConnection,
+    user_id: i64,
+    name: &str,
+    description: Option<&str>,
+) -> Result<i64> {
+    let project_id: i64 = sqlx::query_scalar(
+        "INSERT INTO projects (user_id, name, description) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(description)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'owner')")
+        .bind(project_id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(project_id)
+}
@@ -8,9 +8,13 @@ use serde_json::{json, Value};
 use crate::ctx::Ctx;
 use crate::errors::{Error, Result};
 use crate::web::db::Db;
+use crate::web::member::{require_project_role, ProjectRole};
 use crate::web::project::{
-    CreateProjectPayload, Project, ProjectListQueryParams, UpdateProjectPayload,
+    fetch_project, insert_project, CreateProjectPayload, Project, ProjectListQueryParams,
+    StatusSummary, UpdateProjectPayload, PROJECT_COLUMNS,
 };
+use crate::web::workflow::StatusCategory;
+use crate::web::workflow::create_default_workflow;
 
 pub fn routes(db: Db) -> Router {
     Router::new()
@@ -19,6 +23,7 @@ pub fn routes(db: Db) -> Router {
             "/projects/{id}",
             get(get_project_by_id).put(update_project).delete(delete_project),
         )
+        .route("/projects/{id}/summary", get(get_project_summary))
         .with_state(db)
 }
 
@@ -27,14 +32,13 @@ async fn create_project(
     State(db): State<Db>,
     Json(payload): Json<CreateProjectPayload>,
 ) -> Result<Json<Value>> {
-    let project = sqlx::query_as::<_, Project>(
-        "INSERT INTO projects (user_id, name, description) VALUES ($1, $2, $3) RETURNING *",
-    )
-    .bind(ctx.user.id)
-    .bind(payload.name)
-    .bind(payload.description)
-    .fetch_one(&db)
-    .await?;
+    let mut tx = db.begin().await?;
+
+    let project_id = insert_project(&mut tx, ctx.user.id, &payload.name, payload.description.as_deref()).await?;
+    create_default_workflow(&mut tx, project_id).await?;
+
+    let project = fetch_project(&mut *tx, project_id).await?;
+    tx.commit().await?;
 
     Ok(Json(json!({ "status": "success", "data": { "project": project } })))
 }
@@ -48,16 +52,16 @@ async fn list_projects(
     let limit = params.limit.unwrap_or(10);
     let offset = (page - 1) * limit;
 
-    let projects: Vec<Project> = sqlx::query_as(
-        "SELECT * FROM projects WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
-    )
+    let projects: Vec<Project> = sqlx::query_as(&format!(
+        "SELECT {PROJECT_COLUMNS} FROM projects p JOIN project_members pm ON pm.project_id = p.id WHERE pm.user_id = $1 ORDER BY p.created_at DESC LIMIT $2 OFFSET $3"
+    ))
     .bind(ctx.user.id)
     .bind(limit as i64)
     .bind(offset as i64)
     .fetch_all(&db)
     .await?;
 
-    let total_projects: i64 = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM projects WHERE user_id = $1")
+    let total_projects: i64 = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM project_members WHERE user_id = $1")
         .bind(ctx.user.id)
         .fetch_one(&db)
         .await?;
@@ -81,35 +85,85 @@ async fn get_project_by_id(
     State(db): State<Db>,
     Path(project_id): Path<i64>,
 ) -> Result<Json<Value>> {
-    let project = sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1")
-        .bind(project_id)
-        .fetch_optional(&db)
-        .await?
-        .ok_or(Error::ProjectNotFound)?;
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
 
-    if project.user_id != ctx.user.id {
-        return Err(Error::ProjectUnauthorized);
-    }
+    let project = fetch_project(&db, project_id).await?;
 
     Ok(Json(json!({ "status": "success", "data": { "project": project } })))
 }
 
+/// Estimates against logged and completed work, per workflow status and in
+/// total, for sprint planning.
+async fn get_project_summary(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let statuses: Vec<StatusSummary> = sqlx::query_as(
+        "SELECT ws.key AS status, ws.name, ws.category, COUNT(t.id) AS task_count, \
+            COALESCE(SUM(t.estimate_minutes), 0)::BIGINT AS estimate_minutes, \
+            COALESCE(SUM(t.story_points), 0)::BIGINT AS story_points, \
+            COALESCE(SUM(logged.seconds), 0)::BIGINT AS logged_seconds \
+         FROM workflow_statuses ws \
+         LEFT JOIN tasks t ON t.project_id = ws.project_id AND t.status = ws.key \
+         LEFT JOIN LATERAL (SELECT SUM(te.duration_seconds) AS seconds FROM time_entries te WHERE te.task_id = t.id) logged ON TRUE \
+         WHERE ws.project_id = $1 \
+         GROUP BY ws.id ORDER BY ws.position, ws.id",
+    )
+    .bind(project_id)
+    .fetch_all(&db)
+    .await?;
+
+    let sum = |field: fn(&StatusSummary) -> i64, done_only: bool| -> i64 {
+        statuses
+            .iter()
+            .filter(|status| !done_only || status.category == StatusCategory::Done)
+            .map(field)
+            .sum()
+    };
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "statuses": statuses,
+            "totals": {
+                "task_count": sum(|s| s.task_count, false),
+                "estimate_minutes": sum(|s| s.estimate_minutes, false),
+                "story_points": sum(|s| s.story_points, false),
+                "logged_seconds": sum(|s| s.logged_seconds, false),
+                "completed_task_count": sum(|s| s.task_count, true),
+                "completed_estimate_minutes": sum(|s| s.estimate_minutes, true),
+                "completed_story_points": sum(|s| s.story_points, true)
+            }
+        }
+    })))
+}
+
 async fn update_project(
     ctx: Ctx,
     State(db): State<Db>,
     Path(project_id): Path<i64>,
     Json(payload): Json<UpdateProjectPayload>,
 ) -> Result<Json<Value>> {
-    let project = sqlx::query_as::<_, Project>(
-        "UPDATE projects SET name = COALESCE($1, name), description = COALESCE($2, description) WHERE id = $3 AND user_id = $4 RETURNING *",
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let rows_affected = sqlx::query(
+        "UPDATE projects SET name = COALESCE($1, name), description = COALESCE($2, description) WHERE id = $3",
     )
     .bind(payload.name)
     .bind(payload.description)
     .bind(project_id)
-    .bind(ctx.user.id)
-    .fetch_optional(&db)
+    .execute(&db)
     .await?
-    .ok_or(Error::ProjectUnauthorized)?;
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::ProjectNotFound);
+    }
+
+    let project = fetch_project(&db, project_id).await?;
 
     Ok(Json(json!({ "status": "success", "data": { "project": project } })))
 }
@@ -119,15 +173,16 @@ async fn delete_project(
     State(db): State<Db>,
     Path(project_id): Path<i64>,
 ) -> Result<Json<Value>> {
-    let rows_affected = sqlx::query("DELETE FROM projects WHERE id = $1 AND user_id = $2")
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let rows_affected = sqlx::query("DELETE FROM projects WHERE id = $1")
         .bind(project_id)
-        .bind(ctx.user.id)
         .execute(&db)
         .await?
         .rows_affected();
 
     if rows_affected == 0 {
-        return Err(Error::ProjectUnauthorized);
+        return Err(Error::ProjectNotFound);
     }
 
     Ok(Json(json!({ "status": "success", "message": "Project deleted" })))
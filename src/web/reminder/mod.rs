@@ -0,0 +1,166 @@
+pub mod notifier;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+
+use crate::web::db::Db;
+use crate::web::reminder::notifier::SharedNotifier;
+use crate::Result;
+
+const DEFAULT_THRESHOLDS_MINUTES: [i32; 2] = [24 * 60, 60];
+const DEFAULT_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+const DELIVERY_BATCH: i64 = 100;
+
+/// When reminders fire and how often the worker looks for them.
+#[derive(Debug, Clone)]
+pub struct ReminderConfig {
+    /// A task gets one reminder per threshold once it is due within that many
+    /// minutes.
+    pub thresholds_minutes: Vec<i32>,
+    pub scan_interval: Duration,
+}
+
+impl ReminderConfig {
+    /// Reads `REMINDER_THRESHOLDS_MINUTES` (comma-separated, `1440,60` by
+    /// default) and `REMINDER_INTERVAL_SECONDS` (60 by default).
+    pub fn from_env() -> Self {
+        let thresholds_minutes: Vec<i32> = std::env::var("REMINDER_THRESHOLDS_MINUTES")
+            .ok()
+            .map(|value| {
+                value
+                    .split(',')
+                    .filter_map(|threshold| threshold.trim().parse().ok())
+                    .filter(|threshold| *threshold > 0)
+                    .collect()
+            })
+            .filter(|thresholds: &Vec<i32>| !thresholds.is_empty())
+            .unwrap_or_else(|| DEFAULT_THRESHOLDS_MINUTES.to_vec());
+
+        let scan_interval = std::env::var("REMINDER_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SCAN_INTERVAL);
+
+        Self { thresholds_minutes, scan_interval }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Recipient {
+    pub user_id: i64,
+    pub username: String,
+}
+
+/// A reminder that a task is due soon, as handed to a notifier.
+#[derive(Debug, Serialize, FromRow, Clone)]
+pub struct Reminder {
+    pub id: i64,
+    pub task_id: i64,
+    pub task_title: String,
+    pub project_id: i64,
+    pub due_date: DateTime<Utc>,
+    pub threshold_minutes: i32,
+    /// The task's assignees, or the project's owners when nobody is assigned.
+    #[sqlx(json)]
+    pub recipients: Vec<Recipient>,
+}
+
+/// Creates reminders for open tasks that entered a threshold, then claims and
+/// delivers pending ones. Each reminder is delivered at most once, even across
+/// crashes. Returns how many were delivered.
+pub async fn process_reminders(
+    db: &Db,
+    config: &ReminderConfig,
+    notifier: &SharedNotifier,
+) -> Result<usize> {
+    sqlx::query(
+        "INSERT INTO task_reminders (task_id, threshold_minutes, due_date) \
+         SELECT t.id, th.minutes, t.due_date FROM tasks t \
+         JOIN workflow_statuses ws ON ws.project_id = t.project_id AND ws.key = t.status \
+         CROSS JOIN UNNEST($1::INT[]) AS th(minutes) \
+         WHERE ws.category <> 'done' AND t.due_date > NOW() AND t.due_date <= NOW() + make_interval(mins => th.minutes) \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(&config.thresholds_minutes)
+    .execute(db)
+    .await?;
+
+    // A reminder is stale once the task is done or rescheduled, or when a
+    // closer threshold was reached before it went out.
+    sqlx::query(
+        "UPDATE task_reminders r SET processed_at = NOW(), skipped = TRUE \
+         WHERE r.processed_at IS NULL AND r.claimed_at IS NULL AND ( \
+            NOT EXISTS (SELECT 1 FROM tasks t \
+                JOIN workflow_statuses ws ON ws.project_id = t.project_id AND ws.key = t.status \
+                WHERE t.id = r.task_id AND t.due_date = r.due_date AND ws.category <> 'done') \
+            OR EXISTS (SELECT 1 FROM task_reminders closer \
+                WHERE closer.task_id = r.task_id AND closer.due_date = r.due_date \
+                AND closer.threshold_minutes < r.threshold_minutes))",
+    )
+    .execute(db)
+    .await?;
+
+    // Claiming commits on its own, before delivery: a reminder whose
+    // delivery outcome could not be recorded is never sent again.
+    let claimed: Vec<Reminder> = sqlx::query_as(
+        "WITH claimed AS ( \
+            UPDATE task_reminders SET claimed_at = NOW() WHERE id IN ( \
+                SELECT id FROM task_reminders WHERE processed_at IS NULL AND claimed_at IS NULL \
+                ORDER BY due_date, id LIMIT $1 FOR UPDATE SKIP LOCKED) \
+            RETURNING id, task_id, due_date, threshold_minutes) \
+         SELECT r.id, r.task_id, t.title AS task_title, t.project_id, r.due_date, r.threshold_minutes, \
+            COALESCE( \
+                (SELECT json_agg(json_build_object('user_id', u.id, 'username', u.username) ORDER BY u.id) \
+                    FROM task_assignees ta JOIN users u ON u.id = ta.user_id WHERE ta.task_id = t.id), \
+                (SELECT json_agg(json_build_object('user_id', u.id, 'username', u.username) ORDER BY u.id) \
+                    FROM project_members pm JOIN users u ON u.id = pm.user_id \
+                    WHERE pm.project_id = t.project_id AND pm.role = 'owner'), \
+                '[]') AS recipients \
+         FROM claimed r JOIN tasks t ON t.id = r.task_id ORDER BY r.due_date, r.id",
+    )
+    .bind(DELIVERY_BATCH)
+    .fetch_all(db)
+    .await?;
+
+    let mut delivered_ids = Vec::with_capacity(claimed.len());
+    let mut failed_ids = Vec::new();
+    for reminder in &claimed {
+        match notifier.notify(reminder).await {
+            Ok(()) => delivered_ids.push(reminder.id),
+            Err(err) => {
+                eprintln!("->> {:<12} - failed to deliver reminder {}: {err:?}", "REMINDER", reminder.id);
+                failed_ids.push(reminder.id);
+            }
+        }
+    }
+
+    sqlx::query("UPDATE task_reminders SET processed_at = NOW() WHERE id = ANY($1)")
+        .bind(&delivered_ids)
+        .execute(db)
+        .await?;
+
+    // Released and retried on the next scan.
+    sqlx::query("UPDATE task_reminders SET claimed_at = NULL WHERE id = ANY($1)")
+        .bind(&failed_ids)
+        .execute(db)
+        .await?;
+
+    Ok(delivered_ids.len())
+}
+
+/// Runs [`process_reminders`] periodically for the lifetime of the process.
+pub fn spawn_reminder_worker(db: Db, config: ReminderConfig, notifier: SharedNotifier) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.scan_interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = process_reminders(&db, &config, &notifier).await {
+                eprintln!("->> {:<12} - reminder scan failed: {err:?}", "REMINDER");
+            }
+        }
+    });
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+use crate::web::reminder::Reminder;
+
+/// Delivers due-date reminders to their recipients.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// A failed delivery is retried on the next scan.
+    async fn notify(&self, reminder: &Reminder) -> Result<()>;
+}
+
+pub type SharedNotifier = Arc<dyn Notifier>;
+
+/// Picks the notifier from `REMINDER_NOTIFIER`: `log` (default) or `file`.
+pub fn notifier_from_env() -> SharedNotifier {
+    match std::env::var("REMINDER_NOTIFIER").as_deref() {
+        Ok("file") => Arc::new(FileNotifier::from_env()),
+        _ => Arc::new(LogNotifier),
+    }
+}
+
+/// Prints reminders to stdout.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<()> {
+        let recipients: Vec<&str> = reminder
+            .recipients
+            .iter()
+            .map(|recipient| recipient.username.as_str())
+            .collect();
+        println!(
+            "->> {:<12} - task {} \"{}\" is due at {} ({} min threshold), notifying {}",
+            "REMINDER",
+            reminder.task_id,
+            reminder.task_title,
+            reminder.due_date,
+            reminder.threshold_minutes,
+            recipients.join(", ")
+        );
+        Ok(())
+    }
+}
+
+/// Appends reminders to a file as JSON lines.
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Uses `REMINDER_FILE`, falling back to a file in the system's temporary
+    /// directory.
+    pub fn from_env() -> Self {
+        let path = std::env::var("REMINDER_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| std::env::temp_dir().join("task-manager-reminders.jsonl"));
+        Self::new(path)
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<()> {
+        let mut line = serde_json::to_vec(reminder)?;
+        line.push(b'\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        Ok(())
+    }
+}
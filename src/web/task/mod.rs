@@ -1,16 +1,30 @@
+pub mod rank;
+pub mod recurrence;
 pub mod routes;
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Type};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor, Type};
 use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq)]
-#[sqlx(type_name = "task_status", rename_all = "snake_case")]
-pub enum TaskStatus {
-    Pending,
-    InProgress,
-    Completed,
+use crate::web::activity::record_task_activity;
+use crate::web::checklist::ChecklistProgress;
+use crate::web::label::TaskLabel;
+use crate::web::task::rank::rank_after;
+use crate::web::task::recurrence::{parse_timezone, RRule};
+use crate::web::workflow::{initial_status, StatusCategory};
+use crate::{Error, Result};
+
+/// Declared from least to most urgent, matching the `task_priority` Postgres
+/// enum so `ORDER BY priority` sorts by urgency.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[sqlx(type_name = "task_priority", rename_all = "snake_case")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Urgent,
 }
 
 #[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
@@ -19,11 +33,53 @@ pub struct Task {
     pub project_id: i64,
     pub title: String,
     pub description: Option<String>,
-    #[sqlx(json)]
-    pub status: TaskStatus,
+    /// Key of a status in the project's workflow.
+    pub status: String,
+    pub status_category: StatusCategory,
+    pub priority: TaskPriority,
     pub due_date: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub parent_task_id: Option<i64>,
+    /// iCalendar RRULE, e.g. `FREQ=WEEKLY;BYDAY=MO`.
+    pub recurrence_rule: Option<String>,
+    pub recurrence_timezone: Option<String>,
+    /// Start of the series the rule is evaluated from; the first due date.
+    pub recurrence_start: Option<DateTime<Utc>>,
+    /// Rank within the task's (project, status) column; see `sort=position`.
+    pub position: String,
+    pub estimate_minutes: Option<i32>,
+    pub story_points: Option<i32>,
+    pub assignee_ids: Vec<i64>,
+    pub blocked_by_ids: Vec<i64>,
+    #[sqlx(json)]
+    pub labels: Vec<TaskLabel>,
+    #[sqlx(json)]
+    pub subtasks: SubtaskRollup,
+    #[sqlx(json)]
+    pub checklist_progress: ChecklistProgress,
+    /// Sum of the task's finished time entries.
+    pub time_spent_seconds: i64,
+}
+
+/// Progress of every task below this one in the hierarchy, grouped by status
+/// category.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct SubtaskRollup {
+    pub total: i64,
+    pub todo: i64,
+    pub doing: i64,
+    pub done: i64,
+    /// `None` when the task has no subtasks.
+    pub percent_complete: Option<f64>,
+}
+
+/// A task together with its nested subtasks.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TaskNode {
+    #[serde(flatten)]
+    pub task: Task,
+    pub children: Vec<TaskNode>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -31,20 +87,695 @@ pub struct CreateTaskPayload {
     pub project_id: i64,
     pub title: String,
     pub description: Option<String>,
+    pub priority: Option<TaskPriority>,
     pub due_date: Option<DateTime<Utc>>,
+    pub estimate_minutes: Option<i32>,
+    pub story_points: Option<i32>,
+    pub assignee_ids: Option<Vec<i64>>,
+    pub label_ids: Option<Vec<i64>>,
+    pub parent_task_id: Option<i64>,
+    /// Requires `due_date`, which becomes the first occurrence.
+    pub recurrence_rule: Option<String>,
+    /// IANA time zone the rule is evaluated in. Defaults to `UTC`.
+    pub recurrence_timezone: Option<String>,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, Default)]
 pub struct UpdateTaskPayload {
+    /// Moves the task, with its subtasks, to another project the caller can
+    /// edit. See [`move_to_project`].
+    pub project_id: Option<i64>,
     pub title: Option<String>,
     pub description: Option<String>,
-    pub status: Option<TaskStatus>,
+    /// Must be a status key of the project's workflow.
+    pub status: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<DateTime<Utc>>,
+    /// `null` clears the estimate; omit to leave it unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    pub estimate_minutes: Option<Option<i32>>,
+    /// `null` clears the story points; omit to leave them unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    pub story_points: Option<Option<i32>>,
+    /// Replaces the full assignee set when present.
+    pub assignee_ids: Option<Vec<i64>>,
+    /// Replaces the full label set when present.
+    pub label_ids: Option<Vec<i64>>,
+    /// `null` detaches the task from its parent; omit to leave it unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    pub parent_task_id: Option<Option<i64>>,
+    /// `null` stops the recurrence; omit to leave it unchanged.
+    #[serde(default, deserialize_with = "double_option")]
+    pub recurrence_rule: Option<Option<String>>,
+    pub recurrence_timezone: Option<String>,
+    /// Allows starting or completing a task whose blockers are not completed.
+    #[serde(default)]
+    pub ignore_blockers: bool,
+}
+
+/// Places the task after `after_task_id` and/or before `before_task_id`, both
+/// of which must already be in the target column. With neither, the task goes
+/// to the bottom of the column.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MoveTaskPayload {
+    /// Target column; defaults to the task's current status.
+    pub status: Option<String>,
+    pub after_task_id: Option<i64>,
+    pub before_task_id: Option<i64>,
+    #[serde(default)]
+    pub ignore_blockers: bool,
+}
+
+/// Estimates and story points are counts and cannot be negative.
+pub(crate) fn validate_estimates(estimate_minutes: Option<i32>, story_points: Option<i32>) -> Result<()> {
+    if estimate_minutes.is_some_and(|minutes| minutes < 0) || story_points.is_some_and(|points| points < 0) {
+        return Err(Error::InvalidEstimate);
+    }
+    Ok(())
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from a missing field (`None`).
+pub(crate) fn double_option<'de, T, D>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// `assignee=me`, `assignee=<user id>` or `assignee=none`.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(try_from = "String")]
+pub enum AssigneeFilter {
+    Me,
+    User(i64),
+    Unassigned,
+}
+
+impl TryFrom<String> for AssigneeFilter {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        match value.as_str() {
+            "me" => Ok(AssigneeFilter::Me),
+            "none" => Ok(AssigneeFilter::Unassigned),
+            id => id
+                .parse()
+                .map(AssigneeFilter::User)
+                .map_err(|_| format!("invalid assignee filter: {value}")),
+        }
+    }
+}
+
+/// How repeated `label=` filters combine.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelMatch {
+    #[default]
+    Any,
+    All,
+}
+
+/// Tasks a bulk operation applies to: an explicit list or every visible task
+/// matching the list filters.
+#[derive(Debug, Deserialize)]
+pub struct BulkTaskPayload {
+    pub task_ids: Option<Vec<i64>>,
+    /// Pagination and sorting do not apply and are rejected.
+    pub filter: Option<TaskListQueryParams>,
+    #[serde(flatten)]
+    pub operation: BulkTaskOperation,
+    /// Roll back every change when any task fails. With `false`, failing
+    /// tasks are skipped and the others are committed.
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+}
+
+fn default_atomic() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkTaskOperation {
+    Update { changes: BulkTaskChanges },
+    Delete,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkTaskChanges {
+    /// Status key in the (target) project's workflow.
+    pub status: Option<String>,
     pub due_date: Option<DateTime<Utc>>,
+    /// Replaces the full label set when present.
+    pub label_ids: Option<Vec<i64>>,
+    /// Moves the tasks, with their subtasks, to another project.
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub ignore_blockers: bool,
+}
+
+impl From<&BulkTaskChanges> for UpdateTaskPayload {
+    fn from(changes: &BulkTaskChanges) -> Self {
+        Self {
+            project_id: changes.project_id,
+            status: changes.status.clone(),
+            due_date: changes.due_date,
+            label_ids: changes.label_ids.clone(),
+            ignore_blockers: changes.ignore_blockers,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OccurrenceQueryParams {
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskSortField {
+    CreatedAt,
+    UpdatedAt,
+    DueDate,
+    Priority,
+    Title,
+    /// Board order: by project and status, then by manual rank.
+    Position,
+}
+
+impl TaskSortField {
+    /// Direction used when the sort key does not give one: newest and most
+    /// urgent first, everything else ascending.
+    fn default_direction(self) -> SortDirection {
+        match self {
+            TaskSortField::CreatedAt | TaskSortField::UpdatedAt | TaskSortField::Priority => SortDirection::Desc,
+            TaskSortField::DueDate | TaskSortField::Title | TaskSortField::Position => SortDirection::Asc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// `sort=priority,due_date:asc`: comma-separated fields, each optionally
+/// followed by `:asc` or `:desc`.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct TaskSort(pub Vec<(TaskSortField, SortDirection)>);
+
+impl Default for TaskSort {
+    fn default() -> Self {
+        TaskSort(vec![(TaskSortField::CreatedAt, SortDirection::Desc)])
+    }
+}
+
+impl TryFrom<String> for TaskSort {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        let mut keys = Vec::new();
+        for key in value.split(',').map(str::trim) {
+            let (field, direction) = match key.split_once(':') {
+                Some((field, direction)) => (field, Some(direction)),
+                None => (key, None),
+            };
+            let field = match field {
+                "created_at" => TaskSortField::CreatedAt,
+                "updated_at" => TaskSortField::UpdatedAt,
+                "due_date" => TaskSortField::DueDate,
+                "priority" => TaskSortField::Priority,
+                "title" => TaskSortField::Title,
+                "position" => TaskSortField::Position,
+                _ => return Err(format!("invalid sort field: {field}")),
+            };
+            let direction = match direction {
+                None => field.default_direction(),
+                Some("asc") => SortDirection::Asc,
+                Some("desc") => SortDirection::Desc,
+                Some(direction) => return Err(format!("invalid sort direction: {direction}")),
+            };
+            if keys.iter().any(|(existing, _)| *existing == field) {
+                return Err(format!("duplicate sort field: {key}"));
+            }
+            keys.push((field, direction));
+        }
+        Ok(TaskSort(keys))
+    }
+}
+
+/// Accepts a single value as well as a list, so `"status": "pending"` keeps
+/// working in JSON filters.
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TaskListQueryParams {
     pub page: Option<u32>,
     pub limit: Option<u32>,
-    pub status: Option<TaskStatus>,
-}
\ No newline at end of file
+    pub project_id: Option<i64>,
+    /// Status keys; repeat the parameter to match any of several.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub status: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub assignee: Option<AssigneeFilter>,
+    /// Label names; repeat the parameter to filter on several.
+    #[serde(default)]
+    pub label: Vec<String>,
+    #[serde(default)]
+    pub label_match: LabelMatch,
+    pub due_before: Option<DateTime<Utc>>,
+    pub due_after: Option<DateTime<Utc>>,
+    /// Past due and not in a `Done` status; `false` excludes those tasks.
+    pub overdue: Option<bool>,
+    pub has_due_date: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    /// Case-insensitive text searched for in the title and description.
+    pub q: Option<String>,
+    #[serde(default)]
+    pub sort: TaskSort,
+}
+
+/// Select list producing a [`Task`] row, computed columns included. The
+/// `tasks` table must be aliased as `t`.
+pub(crate) const TASK_COLUMNS: &str = "t.*, \
+    (SELECT ws.category FROM workflow_statuses ws WHERE ws.project_id = t.project_id AND ws.key = t.status) AS status_category, \
+    ARRAY(SELECT ta.user_id FROM task_assignees ta WHERE ta.task_id = t.id ORDER BY ta.user_id) AS assignee_ids, \
+    ARRAY(SELECT td.blocked_by_task_id FROM task_dependencies td WHERE td.task_id = t.id ORDER BY td.blocked_by_task_id) AS blocked_by_ids, \
+    COALESCE((SELECT json_agg(json_build_object('id', l.id, 'name', l.name, 'color', l.color) ORDER BY l.name) \
+        FROM task_labels tl JOIN labels l ON l.id = tl.label_id WHERE tl.task_id = t.id), '[]') AS labels, \
+    (WITH RECURSIVE descendants AS ( \
+        SELECT c.id FROM tasks c WHERE c.parent_task_id = t.id \
+        UNION SELECT c.id FROM tasks c JOIN descendants d ON c.parent_task_id = d.id \
+    ) SELECT json_build_object( \
+        'total', COUNT(*), \
+        'todo', COUNT(*) FILTER (WHERE ws.category = 'todo'), \
+        'doing', COUNT(*) FILTER (WHERE ws.category = 'doing'), \
+        'done', COUNT(*) FILTER (WHERE ws.category = 'done'), \
+        'percent_complete', ROUND(100.0 * COUNT(*) FILTER (WHERE ws.category = 'done') / NULLIF(COUNT(*), 0), 1) \
+    ) FROM descendants d JOIN tasks c ON c.id = d.id \
+    JOIN workflow_statuses ws ON ws.project_id = c.project_id AND ws.key = c.status) AS subtasks, \
+    (SELECT json_build_object('done', COUNT(*) FILTER (WHERE ci.checked), 'total', COUNT(*)) \
+        FROM task_checklist_items ci WHERE ci.task_id = t.id) AS checklist_progress, \
+    (SELECT COALESCE(SUM(te.duration_seconds), 0)::BIGINT FROM time_entries te WHERE te.task_id = t.id) AS time_spent_seconds";
+
+pub(crate) async fn fetch_task<'e>(executor: impl PgExecutor<'e>, task_id: i64) -> Result<Task> {
+    sqlx::query_as::<_, Task>(&format!("SELECT {TASK_COLUMNS} FROM tasks t WHERE t.id = $1"))
+        .bind(task_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or(Error::TaskNotFound)
+}
+
+/// Rank placing a task at the bottom of a (project, status) column.
+pub(crate) async fn next_position<'e>(
+    executor: impl PgExecutor<'e>,
+    project_id: i64,
+    status: &str,
+) -> Result<String> {
+    let last: Option<String> =
+        sqlx::query_scalar("SELECT MAX(position) FROM tasks WHERE project_id = $1 AND status = $2")
+            .bind(project_id)
+            .bind(status)
+            .fetch_one(executor)
+            .await?;
+    Ok(rank_after(last.as_deref()))
+}
+
+/// Replaces a task's assignees, checking every user is a member of the
+/// task's project.
+pub(crate) async fn set_assignees(
+    conn: &mut PgConnection,
+    task_id: i64,
+    project_id: i64,
+    assignee_ids: &[i64],
+) -> Result<()> {
+    let mut assignee_ids = assignee_ids.to_vec();
+    assignee_ids.sort_unstable();
+    assignee_ids.dedup();
+
+    let members: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM project_members WHERE project_id = $1 AND user_id = ANY($2)",
+    )
+    .bind(project_id)
+    .bind(&assignee_ids)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if members != assignee_ids.len() as i64 {
+        return Err(Error::AssigneeNotMember);
+    }
+
+    sqlx::query("DELETE FROM task_assignees WHERE task_id = $1 AND user_id <> ALL($2)")
+        .bind(task_id)
+        .bind(&assignee_ids)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO task_assignees (task_id, user_id) SELECT $1, UNNEST($2::BIGINT[]) ON CONFLICT DO NOTHING",
+    )
+    .bind(task_id)
+    .bind(&assignee_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Checks that `parent_id` is a task in the same project and, when re-parenting
+/// an existing task, that it is not the task itself or one of its descendants.
+///
+/// Hierarchy changes are serialized per project so two concurrent moves cannot
+/// close a cycle between them.
+pub(crate) async fn validate_parent(
+    conn: &mut PgConnection,
+    task_id: Option<i64>,
+    project_id: i64,
+    parent_id: i64,
+) -> Result<()> {
+    sqlx::query("SELECT id FROM projects WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(project_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let parent_project_id: Option<i64> =
+        sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = $1")
+            .bind(parent_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    if parent_project_id != Some(project_id) {
+        return Err(Error::InvalidParentTask);
+    }
+
+    let Some(task_id) = task_id else {
+        return Ok(());
+    };
+
+    let creates_cycle: bool = sqlx::query_scalar(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id FROM tasks WHERE id = $1 \
+            UNION SELECT c.id FROM tasks c JOIN subtree s ON c.parent_task_id = s.id \
+        ) SELECT EXISTS (SELECT 1 FROM subtree WHERE id = $2)",
+    )
+    .bind(task_id)
+    .bind(parent_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if creates_cycle {
+        return Err(Error::TaskHierarchyCycle);
+    }
+    Ok(())
+}
+
+/// Moves a task and all of its subtasks to another project. The task is
+/// detached from its parent; the subtasks keep their hierarchy.
+///
+/// Each moved task keeps its status when the target workflow has the same
+/// key, else takes the target's first status of the same category, else its
+/// initial status, and goes to the bottom of that column. Labels are matched
+/// by name and assignees who are not members of the target project are
+/// dropped. The change to `task` itself is left for the caller to record.
+pub(crate) async fn move_to_project(
+    conn: &mut PgConnection,
+    actor_id: i64,
+    task: &Task,
+    target_project_id: i64,
+) -> Result<()> {
+    sqlx::query("SELECT id FROM projects WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(target_project_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let subtree: Vec<Task> = sqlx::query_as(&format!(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id FROM tasks WHERE id = $1 \
+            UNION SELECT c.id FROM tasks c JOIN subtree s ON c.parent_task_id = s.id \
+        ) SELECT {TASK_COLUMNS} FROM tasks t JOIN subtree st ON st.id = t.id ORDER BY t.status, t.position, t.id FOR UPDATE OF t"
+    ))
+    .bind(task.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for before in &subtree {
+        let mapped: Option<String> = sqlx::query_scalar(
+            "SELECT key FROM workflow_statuses WHERE project_id = $1 AND (key = $2 OR category = $3) \
+             ORDER BY key <> $2, position, id LIMIT 1",
+        )
+        .bind(target_project_id)
+        .bind(&before.status)
+        .bind(before.status_category)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let status = match mapped {
+            Some(status) => status,
+            None => initial_status(&mut *conn, target_project_id).await?,
+        };
+        let position = next_position(&mut *conn, target_project_id, &status).await?;
+
+        sqlx::query(
+            "UPDATE tasks SET project_id = $1, status = $2, position = $3, parent_task_id = CASE WHEN id = $5 THEN NULL ELSE parent_task_id END WHERE id = $4",
+        )
+        .bind(target_project_id)
+        .bind(&status)
+        .bind(position)
+        .bind(before.id)
+        .bind(task.id)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_labels (task_id, label_id) \
+             SELECT tl.task_id, target.id FROM task_labels tl \
+             JOIN labels l ON l.id = tl.label_id \
+             JOIN labels target ON target.project_id = $2 AND target.name = l.name \
+             WHERE tl.task_id = $1 ON CONFLICT DO NOTHING",
+        )
+        .bind(before.id)
+        .bind(target_project_id)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM task_labels tl USING labels l WHERE tl.label_id = l.id AND tl.task_id = $1 AND l.project_id <> $2",
+        )
+        .bind(before.id)
+        .bind(target_project_id)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM task_assignees WHERE task_id = $1 AND user_id NOT IN (SELECT user_id FROM project_members WHERE project_id = $2)",
+        )
+        .bind(before.id)
+        .bind(target_project_id)
+        .execute(&mut *conn)
+        .await?;
+
+        if before.id != task.id {
+            let after = fetch_task(&mut *conn, before.id).await?;
+            record_task_activity(conn, actor_id, Some(before), Some(&after)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalized recurrence settings as stored on a task.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RecurrenceSettings {
+    pub rule: Option<String>,
+    pub timezone: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+}
+
+impl RecurrenceSettings {
+    /// Validates a rule for a series whose first occurrence is `due_date`.
+    pub fn new(
+        rule: Option<&str>,
+        timezone: Option<&str>,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<Self> {
+        let Some(rule) = rule else {
+            return Ok(Self::default());
+        };
+        RRule::parse(rule)?;
+        let timezone = timezone.unwrap_or("UTC");
+        parse_timezone(timezone)?;
+        let start = due_date.ok_or(Error::RecurrenceRequiresDueDate)?;
+
+        Ok(Self {
+            rule: Some(rule.trim().to_string()),
+            timezone: Some(timezone.to_string()),
+            start: Some(start),
+        })
+    }
+
+    /// Applies an update on top of `task`. Changing the rule, its time zone or
+    /// the due date restarts the series from the (new) due date.
+    pub fn for_update(task: &Task, payload: &UpdateTaskPayload) -> Result<Self> {
+        let rule = match &payload.recurrence_rule {
+            Some(rule) => rule.as_deref(),
+            None => task.recurrence_rule.as_deref(),
+        };
+        let changed = payload.recurrence_rule.is_some()
+            || payload.recurrence_timezone.is_some()
+            || payload.due_date.is_some();
+
+        if !changed || rule.is_none() {
+            return match rule {
+                Some(_) => Ok(Self {
+                    rule: task.recurrence_rule.clone(),
+                    timezone: task.recurrence_timezone.clone(),
+                    start: task.recurrence_start,
+                }),
+                None => Ok(Self::default()),
+            };
+        }
+
+        let timezone = payload
+            .recurrence_timezone
+            .as_deref()
+            .or(task.recurrence_timezone.as_deref());
+        Self::new(rule, timezone, payload.due_date.or(task.due_date))
+    }
+}
+
+/// Creates the next occurrence of a recurring task that was just completed,
+/// carrying over its assignees and labels. The series moves to the new task,
+/// so completing the old one again does not spawn a duplicate.
+///
+/// Returns `None` when the task is not recurring or its series has ended.
+pub(crate) async fn spawn_next_occurrence(conn: &mut PgConnection, task: &Task) -> Result<Option<i64>> {
+    let (Some(rule), Some(timezone), Some(start), Some(due_date)) = (
+        &task.recurrence_rule,
+        &task.recurrence_timezone,
+        task.recurrence_start,
+        task.due_date,
+    ) else {
+        return Ok(None);
+    };
+
+    let rule = RRule::parse(rule)?;
+    let tz = parse_timezone(timezone)?;
+    let Some(next_due_date) = rule.occurrences_after(tz, start, due_date, 1).into_iter().next() else {
+        return Ok(None);
+    };
+
+    let status = initial_status(&mut *conn, task.project_id).await?;
+    let position = next_position(&mut *conn, task.project_id, &status).await?;
+
+    let next_id: i64 = sqlx::query_scalar(
+        "INSERT INTO tasks (project_id, title, description, status, priority, due_date, parent_task_id, recurrence_rule, recurrence_timezone, recurrence_start, position, estimate_minutes, story_points) \
+         SELECT project_id, title, description, $3, priority, $2, parent_task_id, recurrence_rule, recurrence_timezone, recurrence_start, $4, estimate_minutes, story_points FROM tasks WHERE id = $1 \
+         RETURNING id",
+    )
+    .bind(task.id)
+    .bind(next_due_date)
+    .bind(status)
+    .bind(position)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query("INSERT INTO task_assignees (task_id, user_id) SELECT $2, user_id FROM task_assignees WHERE task_id = $1")
+        .bind(task.id)
+        .bind(next_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("INSERT INTO task_labels (task_id, label_id) SELECT $2, label_id FROM task_labels WHERE task_id = $1")
+        .bind(task.id)
+        .bind(next_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("UPDATE tasks SET recurrence_rule = NULL, recurrence_timezone = NULL, recurrence_start = NULL WHERE id = $1")
+        .bind(task.id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(Some(next_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_sort(value: &str) -> std::result::Result<Vec<(TaskSortField, SortDirection)>, String> {
+        TaskSort::try_from(value.to_string()).map(|sort| sort.0)
+    }
+
+    #[test]
+    fn sort_uses_default_directions() {
+        assert_eq!(
+            parse_sort("created_at,updated_at,priority,due_date,title,position").unwrap(),
+            vec![
+                (TaskSortField::CreatedAt, SortDirection::Desc),
+                (TaskSortField::UpdatedAt, SortDirection::Desc),
+                (TaskSortField::Priority, SortDirection::Desc),
+                (TaskSortField::DueDate, SortDirection::Asc),
+                (TaskSortField::Title, SortDirection::Asc),
+                (TaskSortField::Position, SortDirection::Asc),
+            ]
+        );
+        assert_eq!(TaskSort::default().0, vec![(TaskSortField::CreatedAt, SortDirection::Desc)]);
+    }
+
+    #[test]
+    fn sort_accepts_explicit_directions() {
+        assert_eq!(
+            parse_sort("due_date:desc, priority:asc").unwrap(),
+            vec![
+                (TaskSortField::DueDate, SortDirection::Desc),
+                (TaskSortField::Priority, SortDirection::Asc),
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_rejects_unknown_field() {
+        assert_eq!(parse_sort("bogus").unwrap_err(), "invalid sort field: bogus");
+        assert!(parse_sort("id:asc").is_err());
+    }
+
+    #[test]
+    fn sort_rejects_unknown_direction() {
+        assert_eq!(parse_sort("title:up").unwrap_err(), "invalid sort direction: up");
+        assert!(parse_sort("title:").is_err());
+        assert!(parse_sort("title:ASC").is_err());
+    }
+
+    #[test]
+    fn sort_rejects_duplicate_field() {
+        assert_eq!(
+            parse_sort("priority,priority:asc").unwrap_err(),
+            "duplicate sort field: priority:asc"
+        );
+    }
+
+    #[test]
+    fn sort_rejects_empty_segment() {
+        assert!(parse_sort("").is_err());
+        assert!(parse_sort("priority,,title").is_err());
+        assert!(parse_sort("priority,").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Lexicographic ranks for manual ordering.
+//!
+//! A rank is a base-36 fraction (`0-9a-z`) compared byte-wise, so the column
+//! must use the `"C"` collation. A new rank can always be generated between
+//! two existing ones, which lets a move rewrite a single row. Generated ranks
+//! never end in `0`, the property that guarantees there is room in between.
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Returns a rank strictly between `before` and `after`, where `None` means
+/// the start or the end of the list respectively.
+///
+/// If the bounds are out of order (possible when two concurrent inserts got
+/// the same rank), the result sorts right after `before`.
+pub fn rank_between(before: Option<&str>, after: Option<&str>) -> String {
+    let before = before.unwrap_or("");
+    let after = after.filter(|after| *after > before);
+    midpoint(before.as_bytes(), after.map(str::as_bytes))
+}
+
+/// Rank for appending after the current last item, if any.
+pub fn rank_after(last: Option<&str>) -> String {
+    rank_between(last, None)
+}
+
+fn digit(byte: u8) -> usize {
+    DIGITS.iter().position(|d| *d == byte).unwrap_or(0)
+}
+
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> String {
+    if let Some(b) = b {
+        // Copy the shared prefix, treating a missing digit in `a` as zero.
+        let mut n = 0;
+        while n < b.len() && a.get(n).copied().unwrap_or(b'0') == b[n] {
+            n += 1;
+        }
+        if n > 0 {
+            let rest = midpoint(a.get(n..).unwrap_or(&[]), Some(&b[n..]));
+            return String::from_utf8_lossy(&b[..n]).into_owned() + &rest;
+        }
+    }
+
+    let digit_a = a.first().map(|d| digit(*d)).unwrap_or(0);
+    let digit_b = match b {
+        Some(b) => b.first().map(|d| digit(*d)).unwrap_or(DIGITS.len()),
+        None => DIGITS.len(),
+    };
+
+    if digit_b - digit_a > 1 {
+        let mid = (digit_a + digit_b).div_ceil(2);
+        return (DIGITS[mid] as char).to_string();
+    }
+
+    match b {
+        Some(b) if b.len() > 1 => (b[0] as char).to_string(),
+        _ => {
+            let rest = midpoint(a.get(1..).unwrap_or(&[]), None);
+            format!("{}{}", DIGITS[digit_a] as char, rest)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks `before < rank < after` byte-wise and that `rank` has room after it.
+    fn assert_between(before: Option<&str>, rank: &str, after: Option<&str>) {
+        assert!(!rank.is_empty());
+        assert!(!rank.ends_with('0'), "{rank:?} ends in 0");
+        assert!(rank.bytes().all(|byte| DIGITS.contains(&byte)), "{rank:?} has invalid digits");
+        if let Some(before) = before {
+            assert!(before.as_bytes() < rank.as_bytes(), "{before:?} < {rank:?}");
+        }
+        if let Some(after) = after {
+            assert!(rank.as_bytes() < after.as_bytes(), "{rank:?} < {after:?}");
+        }
+    }
+
+    #[test]
+    fn empty_list_gets_middle_rank() {
+        assert_eq!(rank_between(None, None), "i");
+        assert_eq!(rank_after(None), "i");
+    }
+
+    #[test]
+    fn open_bounds() {
+        for rank in ["1", "i", "z", "zz", "a1", "0i", "00i"] {
+            assert_between(Some(rank), &rank_between(Some(rank), None), None);
+            assert_between(None, &rank_between(None, Some(rank)), Some(rank));
+        }
+        assert_eq!(rank_between(Some("i"), None), "r");
+        assert_eq!(rank_between(None, Some("i")), "9");
+    }
+
+    #[test]
+    fn adjacent_ranks() {
+        let pairs = [
+            ("a", "a1"),
+            ("a", "a01"),
+            ("a", "b"),
+            ("az", "b"),
+            ("a1", "a2"),
+            ("0i", "1"),
+            ("y", "z"),
+        ];
+        for (before, after) in pairs {
+            let rank = rank_between(Some(before), Some(after));
+            assert_between(Some(before), &rank, Some(after));
+        }
+        assert_eq!(rank_between(Some("a"), Some("a1")), "a0i");
+        assert_eq!(rank_between(Some("a"), Some("b")), "ai");
+    }
+
+    #[test]
+    fn out_of_order_bounds_sort_after_before() {
+        let rank = rank_between(Some("b"), Some("a"));
+        assert_between(Some("b"), &rank, None);
+        let rank = rank_between(Some("b"), Some("b"));
+        assert_between(Some("b"), &rank, None);
+    }
+
+    #[test]
+    fn repeated_front_inserts() {
+        let mut first = rank_after(None);
+        for _ in 0..200 {
+            let rank = rank_between(None, Some(&first));
+            assert_between(None, &rank, Some(&first));
+            first = rank;
+        }
+    }
+
+    #[test]
+    fn repeated_back_inserts() {
+        let mut last = rank_after(None);
+        for _ in 0..200 {
+            let rank = rank_after(Some(&last));
+            assert_between(Some(&last), &rank, None);
+            last = rank;
+        }
+    }
+
+    #[test]
+    fn repeated_inserts_into_the_same_gap() {
+        // Always right after the first item, and always right before the last.
+        let (first, mut after) = ("a".to_string(), "b".to_string());
+        for _ in 0..200 {
+            let rank = rank_between(Some(&first), Some(&after));
+            assert_between(Some(&first), &rank, Some(&after));
+            after = rank;
+        }
+        let (mut before, last) = ("a".to_string(), "b".to_string());
+        for _ in 0..200 {
+            let rank = rank_between(Some(&before), Some(&last));
+            assert_between(Some(&before), &rank, Some(&last));
+            before = rank;
+        }
+    }
+
+    #[test]
+    fn random_inserts_keep_the_list_sorted() {
+        let mut ranks: Vec<String> = Vec::new();
+        let mut seed: u64 = 42;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let index = (seed >> 33) as usize % (ranks.len() + 1);
+            let before = index.checked_sub(1).map(|i| ranks[i].as_str());
+            let after = ranks.get(index).map(String::as_str);
+            let rank = rank_between(before, after);
+            assert_between(before, &rank, after);
+            ranks.insert(index, rank);
+        }
+        assert!(ranks.windows(2).all(|pair| pair[0].as_bytes() < pair[1].as_bytes()));
+    }
+}
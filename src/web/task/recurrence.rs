@@ -0,0 +1,545 @@
+//! A pragmatic subset of iCalendar (RFC 5545) recurrence rules.
+//!
+//! Supported parts: `FREQ` (`DAILY`, `WEEKLY`, `MONTHLY`, `YEARLY`),
+//! `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY` (with ordinals such as `1MO` or
+//! `-1FR` for monthly and yearly rules), `BYMONTHDAY` and `BYMONTH`. Yearly
+//! rules expand `BYDAY`/`BYMONTHDAY` within each selected month. Any other
+//! part is rejected rather than silently ignored.
+//!
+//! Occurrences are computed in the rule's time zone and keep the wall-clock
+//! time of the series start, so a 09:00 weekly task stays at 09:00 across DST
+//! changes.
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::{Error, Result};
+
+/// Upper bound on the periods walked while looking for occurrences, so rules
+/// that can never match (e.g. the 31st of February) terminate.
+const MAX_PERIODS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone)]
+enum Until {
+    Date(NaiveDate),
+    Local(NaiveDateTime),
+    Utc(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone)]
+pub struct RRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<Until>,
+    /// Weekday with an optional ordinal (`Some(-1)` for "last").
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+}
+
+impl RRule {
+    /// Parses the rule body, with or without a leading `RRULE:`.
+    pub fn parse(rule: &str) -> Result<Self> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut freq = None;
+        let mut parsed = RRule {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        };
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or(Error::InvalidRecurrenceRule)?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(Error::InvalidRecurrenceRule),
+                    })
+                }
+                "INTERVAL" => {
+                    parsed.interval = value.parse().map_err(|_| Error::InvalidRecurrenceRule)?;
+                    if parsed.interval == 0 {
+                        return Err(Error::InvalidRecurrenceRule);
+                    }
+                }
+                "COUNT" => {
+                    parsed.count = Some(value.parse().map_err(|_| Error::InvalidRecurrenceRule)?)
+                }
+                "UNTIL" => parsed.until = Some(parse_until(value)?),
+                "BYDAY" => {
+                    parsed.by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<Vec<_>>>()?
+                }
+                "BYMONTHDAY" => {
+                    parsed.by_month_day = value
+                        .split(',')
+                        .map(|day| match day.parse::<i32>() {
+                            Ok(day) if day != 0 && (-31..=31).contains(&day) => Ok(day),
+                            _ => Err(Error::InvalidRecurrenceRule),
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                }
+                "BYMONTH" => {
+                    parsed.by_month = value
+                        .split(',')
+                        .map(|month| match month.parse::<u32>() {
+                            Ok(month) if (1..=12).contains(&month) => Ok(month),
+                            _ => Err(Error::InvalidRecurrenceRule),
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                }
+                "WKST" => {}
+                _ => return Err(Error::InvalidRecurrenceRule),
+            }
+        }
+
+        parsed.freq = freq.ok_or(Error::InvalidRecurrenceRule)?;
+        if parsed.count.is_some() && parsed.until.is_some() {
+            return Err(Error::InvalidRecurrenceRule);
+        }
+        let has_ordinals = parsed.by_day.iter().any(|(ordinal, _)| ordinal.is_some());
+        if has_ordinals && matches!(parsed.freq, Frequency::Daily | Frequency::Weekly) {
+            return Err(Error::InvalidRecurrenceRule);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Occurrences of the series starting at `start` that fall strictly after
+    /// `after`, earliest first, at most `limit` of them.
+    pub fn occurrences_after(
+        &self,
+        tz: Tz,
+        start: DateTime<Utc>,
+        after: DateTime<Utc>,
+        limit: usize,
+    ) -> Vec<DateTime<Utc>> {
+        let local_start = start.with_timezone(&tz).naive_local();
+        let time = local_start.time();
+        let start_date = local_start.date();
+
+        let mut occurrences = Vec::new();
+        let mut emitted = 0u32;
+
+        for period in 0..MAX_PERIODS {
+            let Some(offset) = period.checked_mul(self.interval) else {
+                break;
+            };
+            let Some(dates) = self.expand(start_date, offset) else {
+                break;
+            };
+
+            for date in dates {
+                let Some(occurrence) = resolve_local(tz, date.and_time(time)) else {
+                    continue;
+                };
+                if occurrence < start {
+                    continue;
+                }
+                if self.is_past_until(tz, occurrence) {
+                    return occurrences;
+                }
+                emitted += 1;
+                if self.count.is_some_and(|count| emitted > count) {
+                    return occurrences;
+                }
+                if occurrence > after {
+                    occurrences.push(occurrence);
+                    if occurrences.len() >= limit {
+                        return occurrences;
+                    }
+                }
+            }
+        }
+
+        occurrences
+    }
+
+    /// Candidate dates of the period `offset` frequency units after the one
+    /// containing `start`, in ascending order.
+    fn expand(&self, start: NaiveDate, offset: u32) -> Option<Vec<NaiveDate>> {
+        let mut dates = match self.freq {
+            Frequency::Daily => {
+                let date = start.checked_add_signed(Duration::days(offset as i64))?;
+                vec![date]
+            }
+            Frequency::Weekly => {
+                let week_start = start
+                    .checked_sub_signed(Duration::days(start.weekday().num_days_from_monday() as i64))?
+                    .checked_add_signed(Duration::weeks(offset as i64))?;
+                if self.by_day.is_empty() {
+                    vec![week_start.checked_add_signed(Duration::days(
+                        start.weekday().num_days_from_monday() as i64,
+                    ))?]
+                } else {
+                    (0..7)
+                        .filter_map(|day| week_start.checked_add_signed(Duration::days(day)))
+                        .filter(|date| self.by_day.iter().any(|(_, weekday)| *weekday == date.weekday()))
+                        .collect()
+                }
+            }
+            Frequency::Monthly => {
+                let month_start = start.with_day(1)?.checked_add_months(Months::new(offset))?;
+                self.expand_month(month_start, start.day())
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(offset as i32)?;
+                let months = if self.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                months
+                    .into_iter()
+                    .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+                    .flat_map(|month_start| self.expand_month(month_start, start.day()))
+                    .collect()
+            }
+        };
+
+        // BYDAY and BYMONTHDAY act as filters where they did not drive the
+        // expansion above.
+        if self.freq == Frequency::Daily {
+            if !self.by_day.is_empty() {
+                dates.retain(|date| self.by_day.iter().any(|(_, weekday)| *weekday == date.weekday()));
+            }
+            if !self.by_month_day.is_empty() {
+                dates.retain(|date| self.matches_month_day(*date));
+            }
+        }
+        if !self.by_month.is_empty() {
+            dates.retain(|date| self.by_month.contains(&date.month()));
+        }
+
+        dates.sort_unstable();
+        dates.dedup();
+        Some(dates)
+    }
+
+    fn expand_month(&self, month_start: NaiveDate, default_day: u32) -> Vec<NaiveDate> {
+        let month_days: Vec<NaiveDate> = month_start
+            .iter_days()
+            .take_while(|date| date.month() == month_start.month())
+            .collect();
+
+        if !self.by_month_day.is_empty() {
+            return month_days
+                .iter()
+                .copied()
+                .filter(|date| self.matches_month_day(*date))
+                .filter(|date| self.by_day.is_empty() || self.matches_by_day(*date, &month_days))
+                .collect();
+        }
+
+        if !self.by_day.is_empty() {
+            return month_days
+                .iter()
+                .copied()
+                .filter(|date| self.matches_by_day(*date, &month_days))
+                .collect();
+        }
+
+        month_start.with_day(default_day).into_iter().collect()
+    }
+
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        let days_in_month = date
+            .with_day(1)
+            .and_then(|first| first.checked_add_months(Months::new(1)))
+            .and_then(|next| next.pred_opt())
+            .map(|last| last.day() as i32)
+            .unwrap_or(31);
+        let day = date.day() as i32;
+        self.by_month_day
+            .iter()
+            .any(|&by| by == day || (by < 0 && days_in_month + by + 1 == day))
+    }
+
+    fn matches_by_day(&self, date: NaiveDate, month_days: &[NaiveDate]) -> bool {
+        self.by_day.iter().any(|(ordinal, weekday)| {
+            if date.weekday() != *weekday {
+                return false;
+            }
+            let Some(ordinal) = ordinal else {
+                return true;
+            };
+            let same_weekday: Vec<&NaiveDate> =
+                month_days.iter().filter(|d| d.weekday() == *weekday).collect();
+            let index = if *ordinal > 0 {
+                *ordinal as usize - 1
+            } else {
+                match same_weekday.len().checked_sub(ordinal.unsigned_abs() as usize) {
+                    Some(index) => index,
+                    None => return false,
+                }
+            };
+            same_weekday.get(index) == Some(&&date)
+        })
+    }
+
+    fn is_past_until(&self, tz: Tz, occurrence: DateTime<Utc>) -> bool {
+        match &self.until {
+            None => false,
+            Some(Until::Utc(until)) => occurrence > *until,
+            Some(Until::Local(until)) => occurrence.with_timezone(&tz).naive_local() > *until,
+            Some(Until::Date(until)) => occurrence.with_timezone(&tz).date_naive() > *until,
+        }
+    }
+}
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz> {
+    timezone.parse().map_err(|_| Error::InvalidTimezone)
+}
+
+/// Maps a wall-clock time to an instant. Ambiguous times (DST fall-back) take
+/// the first instance; skipped times (DST spring-forward) move past the gap.
+fn resolve_local(tz: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        LocalResult::None => tz
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+fn parse_until(value: &str) -> Result<Until> {
+    if let Some(value) = value.strip_suffix('Z') {
+        let until = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .map_err(|_| Error::InvalidRecurrenceRule)?;
+        return Ok(Until::Utc(until.and_utc()));
+    }
+    if value.contains('T') {
+        let until = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .map_err(|_| Error::InvalidRecurrenceRule)?;
+        return Ok(Until::Local(until));
+    }
+    let until = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| Error::InvalidRecurrenceRule)?;
+    Ok(Until::Date(until))
+}
+
+fn parse_by_day(value: &str) -> Result<(Option<i32>, Weekday)> {
+    let value = value.trim();
+    // `split_at` below works on bytes.
+    if value.len() < 2 || !value.is_ascii() {
+        return Err(Error::InvalidRecurrenceRule);
+    }
+    let (ordinal, day) = value.split_at(value.len() - 2);
+    let weekday = match day.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(Error::InvalidRecurrenceRule),
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => match ordinal.parse::<i32>() {
+            Ok(n) if n != 0 && (-5..=5).contains(&n) => Some(n),
+            _ => return Err(Error::InvalidRecurrenceRule),
+        },
+    };
+    Ok((ordinal, weekday))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    /// Up to ten occurrences of `rule` from `start` on, including `start`.
+    fn occurrences(rule: &str, tz: &str, start: &str) -> Vec<String> {
+        let start = utc(start);
+        RRule::parse(rule)
+            .unwrap()
+            .occurrences_after(parse_timezone(tz).unwrap(), start, start - Duration::seconds(1), 10)
+            .iter()
+            .map(|occurrence| occurrence.format("%Y-%m-%dT%H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn count_limits_the_series() {
+        assert_eq!(
+            occurrences("RRULE:FREQ=DAILY;COUNT=3", "UTC", "2025-01-01T09:00:00Z"),
+            ["2025-01-01T09:00", "2025-01-02T09:00", "2025-01-03T09:00"]
+        );
+
+        // Occurrences before `after` still count towards COUNT.
+        let rule = RRule::parse("FREQ=DAILY;COUNT=3").unwrap();
+        let start = utc("2025-01-01T09:00:00Z");
+        assert_eq!(
+            rule.occurrences_after(Tz::UTC, start, utc("2025-01-02T09:00:00Z"), 10),
+            [utc("2025-01-03T09:00:00Z")]
+        );
+        assert!(rule
+            .occurrences_after(Tz::UTC, start, utc("2025-01-03T09:00:00Z"), 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;UNTIL=20250115T090000Z", "UTC", "2025-01-01T09:00:00Z"),
+            ["2025-01-01T09:00", "2025-01-08T09:00", "2025-01-15T09:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;UNTIL=20250114", "UTC", "2025-01-01T09:00:00Z"),
+            ["2025-01-01T09:00", "2025-01-08T09:00"]
+        );
+        // A local UNTIL is read in the series' time zone: 09:00 in New York is
+        // before 10:00 there, although 14:00 UTC is not.
+        assert_eq!(
+            occurrences("FREQ=DAILY;UNTIL=20250102T100000", "America/New_York", "2025-01-01T14:00:00Z"),
+            ["2025-01-01T14:00", "2025-01-02T14:00"]
+        );
+    }
+
+    #[test]
+    fn by_day_with_negative_ordinal_picks_last_weekday() {
+        let last_fridays = occurrences("FREQ=MONTHLY;BYDAY=-1FR;COUNT=4", "UTC", "2025-01-01T12:00:00Z");
+        assert_eq!(
+            last_fridays,
+            ["2025-01-31T12:00", "2025-02-28T12:00", "2025-03-28T12:00", "2025-04-25T12:00"]
+        );
+    }
+
+    #[test]
+    fn by_day_with_positive_ordinal_picks_nth_weekday() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYDAY=1MO;COUNT=3", "UTC", "2025-01-01T12:00:00Z"),
+            ["2025-01-06T12:00", "2025-02-03T12:00", "2025-03-03T12:00"]
+        );
+    }
+
+    #[test]
+    fn negative_month_day_is_last_day_of_month() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=4", "UTC", "2024-01-15T08:00:00Z"),
+            ["2024-01-31T08:00", "2024-02-29T08:00", "2024-03-31T08:00", "2024-04-30T08:00"]
+        );
+    }
+
+    #[test]
+    fn month_day_31_skips_short_months() {
+        let expected = ["2025-01-31T08:00", "2025-03-31T08:00", "2025-05-31T08:00", "2025-07-31T08:00"];
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=31;COUNT=4", "UTC", "2025-01-01T08:00:00Z"),
+            expected
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;COUNT=4", "UTC", "2025-01-31T08:00:00Z"),
+            expected
+        );
+    }
+
+    #[test]
+    fn interval_skips_periods() {
+        assert_eq!(
+            occurrences("FREQ=DAILY;INTERVAL=3;COUNT=3", "UTC", "2025-01-01T09:00:00Z"),
+            ["2025-01-01T09:00", "2025-01-04T09:00", "2025-01-07T09:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=4", "UTC", "2025-01-06T10:00:00Z"),
+            ["2025-01-06T10:00", "2025-01-09T10:00", "2025-01-20T10:00", "2025-01-23T10:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY;INTERVAL=3;BYMONTH=2;BYMONTHDAY=29;COUNT=2", "UTC", "2024-01-01T00:00:00Z"),
+            ["2024-02-29T00:00", "2036-02-29T00:00"]
+        );
+    }
+
+    #[test]
+    fn series_keeps_wall_clock_time_across_dst() {
+        // 09:00 in New York is 14:00 UTC before DST starts on 9 March 2025 and
+        // 13:00 UTC after.
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;COUNT=4", "America/New_York", "2025-03-01T14:00:00Z"),
+            ["2025-03-01T14:00", "2025-03-08T14:00", "2025-03-15T13:00", "2025-03-22T13:00"]
+        );
+        // And back to 14:00 UTC after it ends on 2 November 2025.
+        assert_eq!(
+            occurrences("FREQ=DAILY;COUNT=3", "America/New_York", "2025-11-01T13:00:00Z"),
+            ["2025-11-01T13:00", "2025-11-02T14:00", "2025-11-03T14:00"]
+        );
+    }
+
+    #[test]
+    fn skipped_and_repeated_local_times_resolve() {
+        // 02:30 does not exist on 9 March 2025 and moves past the gap to 03:30 EDT.
+        assert_eq!(
+            occurrences("FREQ=DAILY;COUNT=3", "America/New_York", "2025-03-08T07:30:00Z"),
+            ["2025-03-08T07:30", "2025-03-09T07:30", "2025-03-10T06:30"]
+        );
+        // 01:30 happens twice on 2 November 2025; the first (EDT) one is used.
+        assert_eq!(
+            occurrences("FREQ=DAILY;COUNT=3", "America/New_York", "2025-11-01T05:30:00Z"),
+            ["2025-11-01T05:30", "2025-11-02T05:30", "2025-11-03T06:30"]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_or_invalid_rules() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;BYHOUR=9",
+            "FREQ=MONTHLY;BYSETPOS=-1",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=two",
+            "FREQ=DAILY;COUNT=2;UNTIL=20250101",
+            "FREQ=DAILY;UNTIL=2025-01-01",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYDAY=6MO",
+            "FREQ=MONTHLY;BYDAY=XX",
+            "FREQ=MONTHLY;BYDAY=éM",
+            "FREQ=MONTHLY;BYDAY=1é",
+            "FREQ=MONTHLY;BYDAY=MO,é",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=YEARLY;BYMONTH=13",
+            "FREQ",
+        ] {
+            assert!(RRule::parse(rule).is_err(), "{rule:?} should be rejected");
+        }
+        assert!(RRule::parse("RRULE:freq=weekly;wkst=MO;byday=mo,-1fr").is_err());
+        assert!(RRule::parse("RRULE:freq=monthly;wkst=MO;byday=mo,-1fr").is_ok());
+    }
+
+    #[test]
+    fn rule_that_never_matches_terminates() {
+        assert!(occurrences("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", "UTC", "2025-01-01T00:00:00Z").is_empty());
+    }
+}
@@ -1,16 +1,33 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     routing::{get, post},
     Json, Router,
 };
+use axum_extra::extract::Query;
 use serde_json::{json, Value};
+use sqlx::{Connection, Postgres, QueryBuilder};
 
 use crate::ctx::Ctx;
 use crate::errors::{Error, Result};
+use crate::web::activity::record_task_activity;
 use crate::web::db::Db;
+use crate::web::dependency::open_blockers;
+use crate::web::label::set_task_labels;
+use crate::web::member::{require_project_role, require_task_role, ProjectRole};
+use crate::web::task::rank::rank_between;
+use crate::web::task::recurrence::{parse_timezone, RRule};
 use crate::web::task::{
-    CreateTaskPayload, Task, TaskListQueryParams, UpdateTaskPayload,
+    fetch_task, move_to_project, next_position, set_assignees, spawn_next_occurrence,
+    validate_estimates, validate_parent, AssigneeFilter, BulkTaskOperation, BulkTaskPayload,
+    CreateTaskPayload, LabelMatch, MoveTaskPayload, OccurrenceQueryParams, RecurrenceSettings,
+    SortDirection, Task, TaskListQueryParams, TaskNode, TaskSort, TaskSortField,
+    UpdateTaskPayload, TASK_COLUMNS,
 };
+use crate::web::workflow::{
+    ensure_transition_allowed, initial_status, resolve_status, StatusCategory,
+};
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
 
 pub fn routes(db: Db) -> Router {
     Router::new()
@@ -19,6 +36,10 @@ pub fn routes(db: Db) -> Router {
             "/tasks/{id}",
             get(get_task_by_id).put(update_task).delete(delete_task),
         )
+        .route("/tasks/bulk", post(bulk_tasks))
+        .route("/tasks/{id}/move", post(move_task))
+        .route("/tasks/{id}/subtree", get(get_task_subtree))
+        .route("/tasks/{id}/occurrences", get(preview_occurrences))
         .with_state(db)
 }
 
@@ -27,26 +48,55 @@ async fn create_task(
     State(db): State<Db>,
     Json(payload): Json<CreateTaskPayload>,
 ) -> Result<Json<Value>> {
-    let project_user_id: i64 = sqlx::query_scalar("SELECT user_id FROM projects WHERE id = $1")
-        .bind(payload.project_id)
-        .fetch_optional(&db)
-        .await?
-        .ok_or(Error::ProjectNotFound)?;
+    require_project_role(&db, payload.project_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    validate_estimates(payload.estimate_minutes, payload.story_points)?;
+    let recurrence = RecurrenceSettings::new(
+        payload.recurrence_rule.as_deref(),
+        payload.recurrence_timezone.as_deref(),
+        payload.due_date,
+    )?;
 
-    if project_user_id != ctx.user.id {
-        return Err(Error::ProjectUnauthorized);
+    let mut tx = db.begin().await?;
+
+    if let Some(parent_id) = payload.parent_task_id {
+        validate_parent(&mut tx, None, payload.project_id, parent_id).await?;
     }
 
-    let task = sqlx::query_as::<_, Task>(
-        "INSERT INTO tasks (project_id, title, description, due_date) VALUES ($1, $2, $3, $4) RETURNING *",
+    let status = initial_status(&mut *tx, payload.project_id).await?;
+    let position = next_position(&mut *tx, payload.project_id, &status).await?;
+
+    let task_id: i64 = sqlx::query_scalar(
+        "INSERT INTO tasks (project_id, title, description, status, priority, due_date, parent_task_id, recurrence_rule, recurrence_timezone, recurrence_start, position, estimate_minutes, story_points) VALUES ($1, $2, $3, $4, COALESCE($5, 'medium'), $6, $7, $8, $9, $10, $11, $12, $13) RETURNING id",
     )
     .bind(payload.project_id)
     .bind(payload.title)
     .bind(payload.description)
+    .bind(status)
+    .bind(payload.priority)
     .bind(payload.due_date)
-    .fetch_one(&db)
+    .bind(payload.parent_task_id)
+    .bind(recurrence.rule)
+    .bind(recurrence.timezone)
+    .bind(recurrence.start)
+    .bind(position)
+    .bind(payload.estimate_minutes)
+    .bind(payload.story_points)
+    .fetch_one(&mut *tx)
     .await?;
 
+    if let Some(assignee_ids) = &payload.assignee_ids {
+        set_assignees(&mut tx, task_id, payload.project_id, assignee_ids).await?;
+    }
+
+    if let Some(label_ids) = &payload.label_ids {
+        set_task_labels(&mut tx, task_id, payload.project_id, label_ids).await?;
+    }
+
+    let task = fetch_task(&mut *tx, task_id).await?;
+    record_task_activity(&mut tx, ctx.user.id, None, Some(&task)).await?;
+    tx.commit().await?;
+
     Ok(Json(json!({ "status": "success", "data": { "task": task } })))
 }
 
@@ -60,20 +110,18 @@ async fn list_tasks(
     let offset = (page - 1) * limit;
 
     // Base query
-    let mut query_builder = sqlx::QueryBuilder::new("SELECT t.* FROM tasks t JOIN projects p ON t.project_id = p.id WHERE p.user_id = ");
+    let mut query_builder = QueryBuilder::new(format!(
+        "SELECT {TASK_COLUMNS} FROM tasks t JOIN project_members pm ON pm.project_id = t.project_id WHERE pm.user_id = "
+    ));
     query_builder.push_bind(ctx.user.id);
+    push_task_filters(&mut query_builder, &ctx, &params);
 
-    let mut count_query_builder = sqlx::QueryBuilder::new("SELECT COUNT(t.id) FROM tasks t JOIN projects p ON t.project_id = p.id WHERE p.user_id = ");
+    let mut count_query_builder = QueryBuilder::new("SELECT COUNT(t.id) FROM tasks t JOIN project_members pm ON pm.project_id = t.project_id WHERE pm.user_id = ");
     count_query_builder.push_bind(ctx.user.id);
+    push_task_filters(&mut count_query_builder, &ctx, &params);
 
-    if let Some(status) = params.status {
-        query_builder.push(" AND t.status = ");
-        query_builder.push_bind(status);
-        count_query_builder.push(" AND t.status = ");
-        count_query_builder.push_bind(status);
-    }
-
-    query_builder.push(" ORDER BY t.created_at DESC LIMIT ");
+    push_task_sort(&mut query_builder, &params.sort);
+    query_builder.push(" LIMIT ");
     query_builder.push_bind(limit as i64);
     query_builder.push(" OFFSET ");
     query_builder.push_bind(offset as i64);
@@ -96,14 +144,136 @@ async fn list_tasks(
     })))
 }
 
+/// Appends the `AND ...` conditions for the list filters, shared by the page
+/// and count queries.
+fn push_task_filters(builder: &mut QueryBuilder<'_, Postgres>, ctx: &Ctx, params: &TaskListQueryParams) {
+    if let Some(project_id) = params.project_id {
+        builder.push(" AND t.project_id = ");
+        builder.push_bind(project_id);
+    }
+
+    if !params.status.is_empty() {
+        builder.push(" AND t.status = ANY(");
+        builder.push_bind(params.status.clone());
+        builder.push(")");
+    }
+
+    if let Some(priority) = params.priority {
+        builder.push(" AND t.priority = ");
+        builder.push_bind(priority);
+    }
+
+    match params.assignee {
+        Some(AssigneeFilter::Me) => {
+            builder.push(" AND EXISTS (SELECT 1 FROM task_assignees ta WHERE ta.task_id = t.id AND ta.user_id = ");
+            builder.push_bind(ctx.user.id);
+            builder.push(")");
+        }
+        Some(AssigneeFilter::User(user_id)) => {
+            builder.push(" AND EXISTS (SELECT 1 FROM task_assignees ta WHERE ta.task_id = t.id AND ta.user_id = ");
+            builder.push_bind(user_id);
+            builder.push(")");
+        }
+        Some(AssigneeFilter::Unassigned) => {
+            builder.push(" AND NOT EXISTS (SELECT 1 FROM task_assignees ta WHERE ta.task_id = t.id)");
+        }
+        None => {}
+    }
+
+    if !params.label.is_empty() {
+        match params.label_match {
+            LabelMatch::Any => {
+                builder.push(" AND EXISTS (SELECT 1 FROM task_labels tl JOIN labels l ON l.id = tl.label_id WHERE tl.task_id = t.id AND l.name = ANY(");
+                builder.push_bind(params.label.clone());
+                builder.push("))");
+            }
+            LabelMatch::All => {
+                let mut names = params.label.clone();
+                names.sort_unstable();
+                names.dedup();
+                builder.push(" AND (SELECT COUNT(DISTINCT l.name) FROM task_labels tl JOIN labels l ON l.id = tl.label_id WHERE tl.task_id = t.id AND l.name = ANY(");
+                builder.push_bind(names.clone());
+                builder.push(")) = ");
+                builder.push_bind(names.len() as i64);
+            }
+        }
+    }
+
+    if let Some(due_before) = params.due_before {
+        builder.push(" AND t.due_date < ");
+        builder.push_bind(due_before);
+    }
+
+    if let Some(due_after) = params.due_after {
+        builder.push(" AND t.due_date > ");
+        builder.push_bind(due_after);
+    }
+
+    if let Some(overdue) = params.overdue {
+        builder.push(if overdue { " AND " } else { " AND NOT " });
+        builder.push("(t.due_date IS NOT NULL AND t.due_date < NOW() AND EXISTS (SELECT 1 FROM workflow_statuses ws WHERE ws.project_id = t.project_id AND ws.key = t.status AND ws.category <> 'done'))");
+    }
+
+    if let Some(has_due_date) = params.has_due_date {
+        builder.push(if has_due_date { " AND t.due_date IS NOT NULL" } else { " AND t.due_date IS NULL" });
+    }
+
+    if let Some(created_after) = params.created_after {
+        builder.push(" AND t.created_at > ");
+        builder.push_bind(created_after);
+    }
+
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let escaped = q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let pattern = format!("%{escaped}%");
+        builder.push(" AND (t.title ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(" OR t.description ILIKE ");
+        builder.push_bind(pattern);
+        builder.push(")");
+    }
+}
+
+/// Appends the `ORDER BY` for the requested sort keys. Only fixed column
+/// expressions are pushed; `t.id` breaks ties.
+fn push_task_sort(builder: &mut QueryBuilder<'_, Postgres>, sort: &TaskSort) {
+    builder.push(" ORDER BY ");
+    for (field, direction) in &sort.0 {
+        let direction = match direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        let columns: &[&str] = match field {
+            TaskSortField::CreatedAt => &["t.created_at"],
+            TaskSortField::UpdatedAt => &["t.updated_at"],
+            TaskSortField::DueDate => &["t.due_date"],
+            TaskSortField::Priority => &["t.priority"],
+            TaskSortField::Title => &["t.title"],
+            TaskSortField::Position => &[
+                "t.project_id",
+                "(SELECT ws.position FROM workflow_statuses ws WHERE ws.project_id = t.project_id AND ws.key = t.status)",
+                "t.position",
+            ],
+        };
+        for column in columns {
+            builder.push(format!("{column} {direction} NULLS LAST, "));
+        }
+        // Newest first within a priority, whichever way priorities are sorted.
+        if *field == TaskSortField::Priority {
+            builder.push("t.created_at DESC, ");
+        }
+    }
+    builder.push("t.id");
+}
+
 async fn get_task_by_id(
     ctx: Ctx,
     State(db): State<Db>,
     Path(task_id): Path<i64>,
 ) -> Result<Json<Value>> {
-    let task = sqlx::query_as::<_, Task>(
-        "SELECT t.* FROM tasks t JOIN projects p ON t.project_id = p.id WHERE t.id = $1 AND p.user_id = $2",
-    )
+    let task = sqlx::query_as::<_, Task>(&format!(
+        "SELECT {TASK_COLUMNS} FROM tasks t JOIN project_members pm ON pm.project_id = t.project_id WHERE t.id = $1 AND pm.user_id = $2"
+    ))
     .bind(task_id)
     .bind(ctx.user.id)
     .fetch_optional(&db)
@@ -113,26 +283,435 @@ async fn get_task_by_id(
     Ok(Json(json!({ "status": "success", "data": { "task": task } })))
 }
 
+/// Returns the task with all of its descendants nested under `children`.
+async fn get_task_subtree(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let tasks: Vec<Task> = sqlx::query_as(&format!(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id FROM tasks WHERE id = $1 \
+            UNION SELECT c.id FROM tasks c JOIN subtree s ON c.parent_task_id = s.id \
+        ) SELECT {TASK_COLUMNS} FROM tasks t JOIN subtree st ON st.id = t.id ORDER BY t.created_at"
+    ))
+    .bind(task_id)
+    .fetch_all(&db)
+    .await?;
+
+    let mut root = None;
+    let mut children_by_parent: HashMap<i64, Vec<Task>> = HashMap::new();
+    for task in tasks {
+        match task.parent_task_id {
+            Some(parent_id) if task.id != task_id => {
+                children_by_parent.entry(parent_id).or_default().push(task)
+            }
+            _ => root = Some(task),
+        }
+    }
+
+    let root = root.ok_or(Error::TaskNotFound)?;
+    let tree = build_task_tree(root, &mut children_by_parent);
+
+    Ok(Json(json!({ "status": "success", "data": { "task": tree } })))
+}
+
+fn build_task_tree(task: Task, children_by_parent: &mut HashMap<i64, Vec<Task>>) -> TaskNode {
+    let children = children_by_parent
+        .remove(&task.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_task_tree(child, children_by_parent))
+        .collect();
+    TaskNode { task, children }
+}
+
 async fn update_task(
     ctx: Ctx,
     State(db): State<Db>,
     Path(task_id): Path<i64>,
     Json(payload): Json<UpdateTaskPayload>,
 ) -> Result<Json<Value>> {
-    let task = sqlx::query_as::<_, Task>(
-        "UPDATE tasks t SET title = COALESCE($1, t.title), description = COALESCE($2, t.description), status = COALESCE($3, t.status), due_date = COALESCE($4, t.due_date) FROM projects p WHERE t.id = $5 AND t.project_id = p.id AND p.user_id = $6 RETURNING t.*",
+    let mut tx = db.begin().await?;
+    let (task, next_task) = apply_task_update(&mut tx, ctx.user.id, task_id, payload).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "task": task, "next_task": next_task }
+    })))
+}
+
+/// Applies an update on behalf of `actor_id` and records it in the task's
+/// history. Returns the updated task and, when a recurring task was
+/// completed, its next occurrence.
+async fn apply_task_update(
+    conn: &mut PgConnection,
+    actor_id: i64,
+    task_id: i64,
+    payload: UpdateTaskPayload,
+) -> Result<(Task, Option<Task>)> {
+    let (source_project_id, _) = require_task_role(&mut *conn, task_id, actor_id, ProjectRole::Editor).await?;
+
+    validate_estimates(payload.estimate_minutes.flatten(), payload.story_points.flatten())?;
+
+    sqlx::query("SELECT id FROM tasks WHERE id = $1 FOR UPDATE")
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?;
+    let before = fetch_task(&mut *conn, task_id).await?;
+
+    // Moving projects happens first, so the rest of the update is checked
+    // against the target project.
+    let (project_id, current) = match payload.project_id.filter(|id| *id != source_project_id) {
+        Some(target_project_id) => {
+            require_project_role(&mut *conn, target_project_id, actor_id, ProjectRole::Editor).await?;
+            move_to_project(conn, actor_id, &before, target_project_id).await?;
+            (target_project_id, fetch_task(&mut *conn, task_id).await?)
+        }
+        None => (source_project_id, before.clone()),
+    };
+
+    if let Some(Some(parent_id)) = payload.parent_task_id {
+        validate_parent(conn, Some(task_id), project_id, parent_id).await?;
+    }
+
+    // A task changing column via a plain update goes to the bottom of it.
+    let mut new_category = None;
+    let mut position = None;
+    if let Some(status) = payload.status.as_deref().filter(|status| *status != current.status) {
+        let status = resolve_status(&mut *conn, project_id, status).await?;
+        ensure_transition_allowed(&mut *conn, project_id, &current.status, &status.key).await?;
+        ensure_unblocked(conn, task_id, status.category, payload.ignore_blockers).await?;
+        position = Some(next_position(&mut *conn, project_id, &status.key).await?);
+        new_category = Some(status.category);
+    }
+
+    let recurrence = RecurrenceSettings::for_update(&current, &payload)?;
+
+    sqlx::query(
+        "UPDATE tasks t SET title = COALESCE($1, t.title), description = COALESCE($2, t.description), status = COALESCE($3, t.status), priority = COALESCE($4, t.priority), due_date = COALESCE($5, t.due_date), parent_task_id = CASE WHEN $6 THEN $7 ELSE t.parent_task_id END, recurrence_rule = $8, recurrence_timezone = $9, recurrence_start = $10, position = COALESCE($11, t.position), estimate_minutes = CASE WHEN $12 THEN $13 ELSE t.estimate_minutes END, story_points = CASE WHEN $14 THEN $15 ELSE t.story_points END WHERE t.id = $16",
     )
     .bind(payload.title)
     .bind(payload.description)
     .bind(payload.status)
+    .bind(payload.priority)
     .bind(payload.due_date)
+    .bind(payload.parent_task_id.is_some())
+    .bind(payload.parent_task_id.flatten())
+    .bind(recurrence.rule)
+    .bind(recurrence.timezone)
+    .bind(recurrence.start)
+    .bind(position)
+    .bind(payload.estimate_minutes.is_some())
+    .bind(payload.estimate_minutes.flatten())
+    .bind(payload.story_points.is_some())
+    .bind(payload.story_points.flatten())
     .bind(task_id)
-    .bind(ctx.user.id)
-    .fetch_optional(&db)
-    .await?
-    .ok_or(Error::TaskUnauthorized)?;
+    .execute(&mut *conn)
+    .await?;
 
-    Ok(Json(json!({ "status": "success", "data": { "task": task } })))
+    if let Some(assignee_ids) = &payload.assignee_ids {
+        set_assignees(conn, task_id, project_id, assignee_ids).await?;
+    }
+
+    if let Some(label_ids) = &payload.label_ids {
+        set_task_labels(conn, task_id, project_id, label_ids).await?;
+    }
+
+    let next_task = after_status_change(conn, &current, new_category).await?;
+
+    let task = fetch_task(&mut *conn, task_id).await?;
+    record_task_activity(conn, actor_id, Some(&before), Some(&task)).await?;
+    if let Some(next_task) = &next_task {
+        record_task_activity(conn, actor_id, None, Some(next_task)).await?;
+    }
+
+    Ok((task, next_task))
+}
+
+/// Most tasks a single bulk operation may touch.
+const MAX_BULK_TASKS: usize = 500;
+
+/// Applies one update or delete to many tasks in a single transaction. Each
+/// task is processed in its own savepoint with the same checks as the single
+/// task endpoints, so every failure is reported. By default any failure rolls
+/// the whole batch back; with `atomic: false` failing tasks are skipped and
+/// the others are committed.
+async fn bulk_tasks(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Json(payload): Json<BulkTaskPayload>,
+) -> Result<Json<Value>> {
+    let task_ids = match (&payload.task_ids, &payload.filter) {
+        (Some(task_ids), None) => {
+            let mut seen = HashSet::new();
+            let mut task_ids = task_ids.clone();
+            task_ids.retain(|task_id| seen.insert(*task_id));
+            task_ids
+        }
+        (None, Some(filter)) => {
+            if filter.page.is_some() || filter.limit.is_some() || filter.sort != TaskSort::default() {
+                return Err(Error::BulkFilterUnsupported);
+            }
+            let mut query_builder = QueryBuilder::new(
+                "SELECT t.id FROM tasks t JOIN project_members pm ON pm.project_id = t.project_id WHERE pm.user_id = ",
+            );
+            query_builder.push_bind(ctx.user.id);
+            push_task_filters(&mut query_builder, &ctx, filter);
+            query_builder.push(" ORDER BY t.id LIMIT ");
+            query_builder.push_bind(MAX_BULK_TASKS as i64 + 1);
+            query_builder.build_query_scalar().fetch_all(&db).await?
+        }
+        _ => return Err(Error::BulkTargetMissing),
+    };
+
+    if task_ids.len() > MAX_BULK_TASKS {
+        return Err(Error::BulkTooManyTasks);
+    }
+
+    let mut tx = db.begin().await?;
+    let mut results = Vec::with_capacity(task_ids.len());
+    let mut failed = 0;
+
+    for task_id in task_ids {
+        let mut savepoint = Connection::begin(&mut *tx).await?;
+        let outcome = match &payload.operation {
+            BulkTaskOperation::Update { changes } => {
+                apply_task_update(&mut savepoint, ctx.user.id, task_id, changes.into())
+                    .await
+                    .map(|(task, next_task)| json!({ "task": task, "next_task": next_task }))
+            }
+            BulkTaskOperation::Delete => delete_task_tree(&mut savepoint, ctx.user.id, task_id)
+                .await
+                .map(|_| json!({})),
+        };
+
+        match outcome {
+            Ok(mut result) => {
+                savepoint.commit().await?;
+                result["task_id"] = json!(task_id);
+                result["status"] = json!("success");
+                results.push(result);
+            }
+            Err(err) => {
+                savepoint.rollback().await?;
+                failed += 1;
+                let (code, mut result) = err.status_and_body();
+                result["task_id"] = json!(task_id);
+                result["code"] = json!(code.as_u16());
+                results.push(result);
+            }
+        }
+    }
+
+    let rolled_back = payload.atomic && failed > 0;
+    if rolled_back {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "results": results,
+            "succeeded": results.len() - failed,
+            "failed": failed,
+            "rolled_back": rolled_back
+        }
+    })))
+}
+
+/// Moves a task within its board column or into another one. Only the moved
+/// task's row is rewritten; its new rank is generated between the neighbours.
+async fn move_task(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<MoveTaskPayload>,
+) -> Result<Json<Value>> {
+    let (project_id, _) = require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let mut tx = db.begin().await?;
+
+    sqlx::query("SELECT id FROM tasks WHERE id = $1 FOR UPDATE")
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Serialize reorders within the project so two moves into the same gap
+    // cannot be given the same rank.
+    sqlx::query("SELECT id FROM projects WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let before = fetch_task(&mut *tx, task_id).await?;
+    let target = payload.status.as_deref().unwrap_or(&before.status);
+    let target = resolve_status(&mut *tx, project_id, target).await?;
+    let status = target.key;
+    let new_category = Some(target.category).filter(|_| status != before.status);
+
+    ensure_transition_allowed(&mut *tx, project_id, &before.status, &status).await?;
+    if let Some(category) = new_category {
+        ensure_unblocked(&mut tx, task_id, category, payload.ignore_blockers).await?;
+    }
+
+    let after_position = match payload.after_task_id {
+        Some(id) => Some(neighbour_position(&mut tx, task_id, project_id, &status, id).await?),
+        None => None,
+    };
+    let before_position = match payload.before_task_id {
+        Some(id) => Some(neighbour_position(&mut tx, task_id, project_id, &status, id).await?),
+        None => None,
+    };
+
+    let (lower, upper) = match (after_position, before_position) {
+        (Some(lower), Some(upper)) => {
+            if lower >= upper {
+                return Err(Error::InvalidMoveTarget);
+            }
+            (Some(lower), Some(upper))
+        }
+        (Some(lower), None) => {
+            let upper: Option<String> = sqlx::query_scalar(
+                "SELECT MIN(position) FROM tasks WHERE project_id = $1 AND status = $2 AND position > $3 AND id <> $4",
+            )
+            .bind(project_id)
+            .bind(&status)
+            .bind(&lower)
+            .bind(task_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            (Some(lower), upper)
+        }
+        (None, upper) => {
+            let lower: Option<String> = sqlx::query_scalar(
+                "SELECT MAX(position) FROM tasks WHERE project_id = $1 AND status = $2 AND ($3::TEXT IS NULL OR position < $3) AND id <> $4",
+            )
+            .bind(project_id)
+            .bind(&status)
+            .bind(&upper)
+            .bind(task_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            (lower, upper)
+        }
+    };
+
+    sqlx::query("UPDATE tasks SET status = $1, position = $2 WHERE id = $3")
+        .bind(status)
+        .bind(rank_between(lower.as_deref(), upper.as_deref()))
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let next_task = after_status_change(&mut tx, &before, new_category).await?;
+
+    let task = fetch_task(&mut *tx, task_id).await?;
+    record_task_activity(&mut tx, ctx.user.id, Some(&before), Some(&task)).await?;
+    if let Some(next_task) = &next_task {
+        record_task_activity(&mut tx, ctx.user.id, None, Some(next_task)).await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "task": task, "next_task": next_task }
+    })))
+}
+
+/// Position of a neighbour given to [`move_task`], which must be another task
+/// in the target column.
+async fn neighbour_position(
+    conn: &mut PgConnection,
+    task_id: i64,
+    project_id: i64,
+    status: &str,
+    neighbour_id: i64,
+) -> Result<String> {
+    if neighbour_id == task_id {
+        return Err(Error::InvalidMoveTarget);
+    }
+
+    sqlx::query_scalar("SELECT position FROM tasks WHERE id = $1 AND project_id = $2 AND status = $3")
+        .bind(neighbour_id)
+        .bind(project_id)
+        .bind(status)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(Error::InvalidMoveTarget)
+}
+
+/// Starting or completing a task requires its blockers to be done, unless the
+/// caller explicitly overrides it.
+async fn ensure_unblocked(
+    conn: &mut PgConnection,
+    task_id: i64,
+    category: StatusCategory,
+    ignore_blockers: bool,
+) -> Result<()> {
+    if matches!(category, StatusCategory::Doing | StatusCategory::Done)
+        && !ignore_blockers
+        && !open_blockers(conn, task_id).await?.is_empty()
+    {
+        return Err(Error::TaskBlocked);
+    }
+    Ok(())
+}
+
+/// Side effects of a status change, run after the task row was updated.
+/// Returns the next occurrence when a recurring task enters a `Done` status.
+async fn after_status_change(
+    conn: &mut PgConnection,
+    before: &Task,
+    new_category: Option<StatusCategory>,
+) -> Result<Option<Task>> {
+    if new_category != Some(StatusCategory::Done) || before.status_category == StatusCategory::Done {
+        return Ok(None);
+    }
+
+    let completed = fetch_task(&mut *conn, before.id).await?;
+    match spawn_next_occurrence(conn, &completed).await? {
+        Some(next_id) => Ok(Some(fetch_task(&mut *conn, next_id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Upcoming due dates of a recurring task after its current one.
+async fn preview_occurrences(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Query(params): Query<OccurrenceQueryParams>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let task = fetch_task(&db, task_id).await?;
+    let (Some(rule), Some(timezone), Some(start), Some(due_date)) = (
+        &task.recurrence_rule,
+        &task.recurrence_timezone,
+        task.recurrence_start,
+        task.due_date,
+    ) else {
+        return Err(Error::TaskNotRecurring);
+    };
+
+    let count = params.count.unwrap_or(5).clamp(1, 50) as usize;
+    let occurrences = RRule::parse(rule)?.occurrences_after(parse_timezone(timezone)?, start, due_date, count);
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "due_date": due_date,
+            "recurrence_rule": rule,
+            "recurrence_timezone": timezone,
+            "occurrences": occurrences
+        }
+    })))
 }
 
 async fn delete_task(
@@ -140,18 +719,40 @@ async fn delete_task(
     State(db): State<Db>,
     Path(task_id): Path<i64>,
 ) -> Result<Json<Value>> {
-    let rows_affected = sqlx::query(
-        "DELETE FROM tasks t USING projects p WHERE t.id = $1 AND t.project_id = p.id AND p.user_id = $2",
-    )
+    let mut tx = db.begin().await?;
+    delete_task_tree(&mut tx, ctx.user.id, task_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Task deleted" })))
+}
+
+/// Deletes a task, and with it its subtasks, on behalf of `actor_id`.
+async fn delete_task_tree(conn: &mut PgConnection, actor_id: i64, task_id: i64) -> Result<()> {
+    require_task_role(&mut *conn, task_id, actor_id, ProjectRole::Editor).await?;
+
+    // Subtasks are deleted with their parent; record each of them.
+    let deleted: Vec<Task> = sqlx::query_as(&format!(
+        "WITH RECURSIVE subtree AS ( \
+            SELECT id FROM tasks WHERE id = $1 \
+            UNION SELECT c.id FROM tasks c JOIN subtree s ON c.parent_task_id = s.id \
+        ) SELECT {TASK_COLUMNS} FROM tasks t JOIN subtree st ON st.id = t.id ORDER BY t.id FOR UPDATE OF t"
+    ))
     .bind(task_id)
-    .bind(ctx.user.id)
-    .execute(&db)
-    .await?
-    .rows_affected();
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let rows_affected = sqlx::query("DELETE FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
 
     if rows_affected == 0 {
-        return Err(Error::TaskUnauthorized);
+        return Err(Error::TaskNotFound);
     }
 
-    Ok(Json(json!({ "status": "success", "message": "Task deleted" })))
-}
\ No newline at end of file
+    for task in &deleted {
+        record_task_activity(conn, actor_id, Some(task), None).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::web::task::TaskPriority;
+use crate::web::workflow::{StatusCategory, WorkflowTransition};
+
+/// A workflow status captured by a template.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
+pub struct TemplateStatus {
+    pub key: String,
+    pub name: String,
+    pub category: StatusCategory,
+    pub restricted: bool,
+}
+
+/// A project label captured by a template.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
+pub struct TemplateLabel {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct ProjectTemplate {
+    pub id: i64,
+    pub user_id: i64,
+    /// Name of the projects created from the template.
+    pub name: String,
+    pub description: Option<String>,
+    /// Empty when projects get the default workflow.
+    #[sqlx(json)]
+    pub workflow: Vec<TemplateStatus>,
+    /// Allowed status changes between the `workflow` statuses.
+    #[sqlx(json)]
+    pub transitions: Vec<WorkflowTransition>,
+    #[sqlx(json)]
+    pub labels: Vec<TemplateLabel>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A task created when the template is instantiated. New tasks start in the
+/// project's initial status.
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct TemplateTask {
+    pub id: i64,
+    pub template_id: i64,
+    pub position: i32,
+    /// `position` of the parent task in the same template.
+    pub parent_position: Option<i32>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: TaskPriority,
+    /// Due date relative to the anchor date.
+    pub due_offset_minutes: Option<i64>,
+    pub estimate_minutes: Option<i32>,
+    pub story_points: Option<i32>,
+    pub label_names: Vec<String>,
+    pub checklist: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SaveTemplatePayload {
+    /// Defaults to the project's name.
+    pub name: Option<String>,
+    /// Date due dates are made relative to. Defaults to the project's creation
+    /// date.
+    pub anchor_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InstantiateTemplatePayload {
+    /// Task due dates are set relative to this date.
+    pub anchor_date: DateTime<Utc>,
+    /// Defaults to the template's name.
+    pub name: Option<String>,
+}
@@ -0,0 +1,338 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::activity::record_task_activity;
+use crate::web::db::Db;
+use crate::web::member::{require_project_role, ProjectRole};
+use crate::web::project::{fetch_project, insert_project};
+use crate::web::task::{fetch_task, next_position, TaskPriority};
+use crate::web::template::{
+    InstantiateTemplatePayload, ProjectTemplate, SaveTemplatePayload, TemplateLabel,
+    TemplateStatus, TemplateTask,
+};
+use crate::web::workflow::{create_default_workflow, initial_status, WorkflowTransition};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route("/templates", get(list_templates))
+        .route(
+            "/templates/{id}",
+            get(get_template).delete(delete_template),
+        )
+        .route("/templates/{id}/instantiate", post(instantiate_template))
+        .route("/projects/{id}/template", post(save_project_as_template))
+        .with_state(db)
+}
+
+/// A project task as captured into a template.
+#[derive(FromRow)]
+struct ProjectTaskRow {
+    id: i64,
+    parent_task_id: Option<i64>,
+    title: String,
+    description: Option<String>,
+    priority: TaskPriority,
+    due_date: Option<DateTime<Utc>>,
+    estimate_minutes: Option<i32>,
+    story_points: Option<i32>,
+    label_names: Vec<String>,
+    checklist: Vec<String>,
+}
+
+/// Lists the caller's templates, newest first.
+async fn list_templates(ctx: Ctx, State(db): State<Db>) -> Result<Json<Value>> {
+    let templates: Vec<ProjectTemplate> = sqlx::query_as(
+        "SELECT * FROM project_templates WHERE user_id = $1 ORDER BY created_at DESC, id DESC",
+    )
+    .bind(ctx.user.id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "templates": templates } })))
+}
+
+async fn get_template(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(template_id): Path<i64>,
+) -> Result<Json<Value>> {
+    let template = fetch_template(&db, ctx.user.id, template_id).await?;
+
+    let tasks: Vec<TemplateTask> = sqlx::query_as(
+        "SELECT * FROM template_tasks WHERE template_id = $1 ORDER BY position",
+    )
+    .bind(template_id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "template": template, "tasks": tasks }
+    })))
+}
+
+async fn delete_template(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(template_id): Path<i64>,
+) -> Result<Json<Value>> {
+    let rows_affected = sqlx::query("DELETE FROM project_templates WHERE id = $1 AND user_id = $2")
+        .bind(template_id)
+        .bind(ctx.user.id)
+        .execute(&db)
+        .await?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::TemplateNotFound);
+    }
+
+    Ok(Json(json!({ "status": "success", "message": "Template deleted" })))
+}
+
+/// Captures the project's workflow and its transitions, labels and tasks as a
+/// new template owned by the caller. Due dates are stored relative to the
+/// anchor date.
+async fn save_project_as_template(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Json(payload): Json<SaveTemplatePayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let mut tx = db.begin().await?;
+
+    let project = fetch_project(&mut *tx, project_id).await?;
+    let anchor_date = payload
+        .anchor_date
+        .or(project.created_at)
+        .unwrap_or_else(Utc::now);
+
+    let workflow: Vec<TemplateStatus> = sqlx::query_as(
+        "SELECT key, name, category, restricted FROM workflow_statuses WHERE project_id = $1 ORDER BY position, id",
+    )
+    .bind(project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let transitions: Vec<WorkflowTransition> = sqlx::query_as(
+        "SELECT fs.key AS \"from\", ts.key AS \"to\" FROM workflow_transitions wt \
+         JOIN workflow_statuses fs ON fs.id = wt.from_status_id \
+         JOIN workflow_statuses ts ON ts.id = wt.to_status_id \
+         WHERE fs.project_id = $1 ORDER BY fs.position, fs.id, ts.position, ts.id",
+    )
+    .bind(project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let labels: Vec<TemplateLabel> =
+        sqlx::query_as("SELECT name, color FROM labels WHERE project_id = $1 ORDER BY name")
+            .bind(project_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+    let template = sqlx::query_as::<_, ProjectTemplate>(
+        "INSERT INTO project_templates (user_id, name, description, workflow, transitions, labels) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+    )
+    .bind(ctx.user.id)
+    .bind(payload.name.unwrap_or(project.name))
+    .bind(project.description)
+    .bind(sqlx::types::Json(&workflow))
+    .bind(sqlx::types::Json(&transitions))
+    .bind(sqlx::types::Json(&labels))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Parents before their subtasks, then in board order.
+    let project_tasks: Vec<ProjectTaskRow> = sqlx::query_as(
+        "WITH RECURSIVE tree AS ( \
+            SELECT id, 0 AS depth FROM tasks WHERE project_id = $1 AND parent_task_id IS NULL \
+            UNION ALL SELECT c.id, tree.depth + 1 FROM tasks c JOIN tree ON c.parent_task_id = tree.id \
+        ) SELECT t.id, t.parent_task_id, t.title, t.description, t.priority, t.due_date, t.estimate_minutes, t.story_points, \
+            ARRAY(SELECT l.name FROM task_labels tl JOIN labels l ON l.id = tl.label_id WHERE tl.task_id = t.id ORDER BY l.name) AS label_names, \
+            ARRAY(SELECT ci.text FROM task_checklist_items ci WHERE ci.task_id = t.id ORDER BY ci.position, ci.id) AS checklist \
+        FROM tree JOIN tasks t ON t.id = tree.id \
+        JOIN workflow_statuses ws ON ws.project_id = t.project_id AND ws.key = t.status \
+        ORDER BY tree.depth, ws.position, t.position, t.id",
+    )
+    .bind(project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut positions: HashMap<i64, i32> = HashMap::new();
+    for (position, task) in project_tasks.into_iter().enumerate() {
+        let position = position as i32;
+        let parent_position = task.parent_task_id.and_then(|parent_id| positions.get(&parent_id).copied());
+        let due_offset_minutes = task.due_date.map(|due_date| (due_date - anchor_date).num_minutes());
+
+        sqlx::query(
+            "INSERT INTO template_tasks (template_id, position, parent_position, title, description, priority, due_offset_minutes, estimate_minutes, story_points, label_names, checklist) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(template.id)
+        .bind(position)
+        .bind(parent_position)
+        .bind(task.title)
+        .bind(task.description)
+        .bind(task.priority)
+        .bind(due_offset_minutes)
+        .bind(task.estimate_minutes)
+        .bind(task.story_points)
+        .bind(task.label_names)
+        .bind(task.checklist)
+        .execute(&mut *tx)
+        .await?;
+
+        positions.insert(task.id, position);
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "template": template, "task_count": positions.len() }
+    })))
+}
+
+/// Creates a project from the template, with its workflow and transitions,
+/// labels and tasks, in one transaction. Task due dates are placed relative to
+/// `anchor_date`.
+async fn instantiate_template(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(template_id): Path<i64>,
+    Json(payload): Json<InstantiateTemplatePayload>,
+) -> Result<Json<Value>> {
+    let template = fetch_template(&db, ctx.user.id, template_id).await?;
+
+    let mut tx = db.begin().await?;
+
+    let name = payload.name.unwrap_or(template.name);
+    let project_id = insert_project(&mut tx, ctx.user.id, &name, template.description.as_deref()).await?;
+
+    if template.workflow.is_empty() {
+        create_default_workflow(&mut tx, project_id).await?;
+    }
+    for (position, status) in template.workflow.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO workflow_statuses (project_id, key, name, category, position, restricted) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(project_id)
+        .bind(&status.key)
+        .bind(&status.name)
+        .bind(status.category)
+        .bind(position as i32)
+        .bind(status.restricted)
+        .execute(&mut *tx)
+        .await?;
+    }
+    for transition in &template.transitions {
+        sqlx::query(
+            "INSERT INTO workflow_transitions (from_status_id, to_status_id) \
+             SELECT fs.id, ts.id FROM workflow_statuses fs JOIN workflow_statuses ts ON ts.project_id = fs.project_id \
+             WHERE fs.project_id = $1 AND fs.key = $2 AND ts.key = $3",
+        )
+        .bind(project_id)
+        .bind(&transition.from)
+        .bind(&transition.to)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for label in &template.labels {
+        sqlx::query("INSERT INTO labels (project_id, name, color) VALUES ($1, $2, $3)")
+            .bind(project_id)
+            .bind(&label.name)
+            .bind(&label.color)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let template_tasks: Vec<TemplateTask> = sqlx::query_as(
+        "SELECT * FROM template_tasks WHERE template_id = $1 ORDER BY position",
+    )
+    .bind(template_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let status = initial_status(&mut *tx, project_id).await?;
+    let mut task_ids: HashMap<i32, i64> = HashMap::new();
+
+    for template_task in &template_tasks {
+        let position = next_position(&mut *tx, project_id, &status).await?;
+        let parent_task_id = template_task
+            .parent_position
+            .and_then(|parent_position| task_ids.get(&parent_position).copied());
+        let due_date = template_task
+            .due_offset_minutes
+            .map(|offset| payload.anchor_date + Duration::minutes(offset));
+
+        let task_id: i64 = sqlx::query_scalar(
+            "INSERT INTO tasks (project_id, title, description, status, priority, due_date, parent_task_id, position, estimate_minutes, story_points) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id",
+        )
+        .bind(project_id)
+        .bind(&template_task.title)
+        .bind(&template_task.description)
+        .bind(&status)
+        .bind(template_task.priority)
+        .bind(due_date)
+        .bind(parent_task_id)
+        .bind(position)
+        .bind(template_task.estimate_minutes)
+        .bind(template_task.story_points)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_labels (task_id, label_id) SELECT $1, id FROM labels WHERE project_id = $2 AND name = ANY($3)",
+        )
+        .bind(task_id)
+        .bind(project_id)
+        .bind(&template_task.label_names)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO task_checklist_items (task_id, text, position) \
+             SELECT $1, item.text, item.ord - 1 FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS item(text, ord)",
+        )
+        .bind(task_id)
+        .bind(&template_task.checklist)
+        .execute(&mut *tx)
+        .await?;
+
+        let task = fetch_task(&mut *tx, task_id).await?;
+        record_task_activity(&mut tx, ctx.user.id, None, Some(&task)).await?;
+
+        task_ids.insert(template_task.position, task_id);
+    }
+
+    let project = fetch_project(&mut *tx, project_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "project": project, "task_count": task_ids.len() }
+    })))
+}
+
+/// Templates are private to the user who saved them.
+async fn fetch_template(db: &Db, user_id: i64, template_id: i64) -> Result<ProjectTemplate> {
+    sqlx::query_as::<_, ProjectTemplate>("SELECT * FROM project_templates WHERE id = $1 AND user_id = $2")
+        .bind(template_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or(Error::TemplateNotFound)
+}
@@ -0,0 +1,41 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct TimeEntry {
+    pub id: i64,
+    pub task_id: i64,
+    /// `None` once the user's account has been deleted.
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the timer is running.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StartTimerPayload {
+    pub note: Option<String>,
+}
+
+/// A manually logged, already finished period of work.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTimeEntryPayload {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTimeEntryPayload {
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+}
@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::db::Db;
+use crate::web::member::{require_task_role, ProjectRole};
+use crate::web::time_entry::{
+    CreateTimeEntryPayload, StartTimerPayload, TimeEntry, UpdateTimeEntryPayload,
+};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route(
+            "/tasks/{id}/time-entries",
+            get(list_entries).post(create_entry),
+        )
+        .route(
+            "/tasks/{id}/time-entries/{entry_id}",
+            put(update_entry).delete(delete_entry),
+        )
+        .route("/tasks/{id}/timer/start", post(start_timer))
+        .route("/tasks/{id}/timer/stop", post(stop_timer))
+        .route("/timer", get(get_running_timer))
+        .with_state(db)
+}
+
+const ENTRY_SELECT: &str = "SELECT te.id, te.task_id, te.user_id, u.username, te.started_at, te.ended_at, te.duration_seconds, te.note, te.created_at FROM time_entries te LEFT JOIN users u ON u.id = te.user_id";
+
+/// Lists a task's time entries, newest first, with the total of the finished
+/// ones.
+async fn list_entries(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let entries: Vec<TimeEntry> = sqlx::query_as(&format!(
+        "{ENTRY_SELECT} WHERE te.task_id = $1 ORDER BY te.started_at DESC, te.id DESC"
+    ))
+    .bind(task_id)
+    .fetch_all(&db)
+    .await?;
+
+    let total_seconds: i64 = entries.iter().filter_map(|entry| entry.duration_seconds).sum();
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "entries": entries, "total_seconds": total_seconds }
+    })))
+}
+
+async fn create_entry(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    Json(payload): Json<CreateTimeEntryPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    if payload.ended_at <= payload.started_at {
+        return Err(Error::InvalidTimeRange);
+    }
+
+    let entry_id: i64 = sqlx::query_scalar(
+        "INSERT INTO time_entries (task_id, user_id, started_at, ended_at, note) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .bind(payload.started_at)
+    .bind(payload.ended_at)
+    .bind(payload.note)
+    .fetch_one(&db)
+    .await?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "entry": entry } })))
+}
+
+/// Only the user who logged an entry may edit it.
+async fn update_entry(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, entry_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateTimeEntryPayload>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+    if entry.user_id != Some(ctx.user.id) {
+        return Err(Error::TimeEntryUnauthorized);
+    }
+
+    let started_at = payload.started_at.unwrap_or(entry.started_at);
+    if let Some(ended_at) = payload.ended_at.or(entry.ended_at) {
+        if ended_at <= started_at {
+            return Err(Error::InvalidTimeRange);
+        }
+    }
+
+    sqlx::query(
+        "UPDATE time_entries SET started_at = $1, ended_at = COALESCE($2, ended_at), note = COALESCE($3, note) WHERE id = $4",
+    )
+    .bind(started_at)
+    .bind(payload.ended_at)
+    .bind(payload.note)
+    .bind(entry_id)
+    .execute(&db)
+    .await?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "entry": entry } })))
+}
+
+/// The user who logged an entry or a project owner may delete it.
+async fn delete_entry(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((task_id, entry_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    let (_, role) = require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+    if entry.user_id != Some(ctx.user.id) && role != ProjectRole::Owner {
+        return Err(Error::TimeEntryUnauthorized);
+    }
+
+    sqlx::query("DELETE FROM time_entries WHERE id = $1")
+        .bind(entry_id)
+        .execute(&db)
+        .await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Time entry deleted" })))
+}
+
+/// Starts a timer on the task. A user can only have one running timer.
+async fn start_timer(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+    payload: Option<Json<StartTimerPayload>>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let note = payload.and_then(|Json(payload)| payload.note);
+
+    let entry_id: i64 = sqlx::query_scalar(
+        "INSERT INTO time_entries (task_id, user_id, started_at, note) VALUES ($1, $2, NOW(), $3) RETURNING id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .bind(note)
+    .fetch_one(&db)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => Error::TimerAlreadyRunning,
+        err => Error::SqlxError(err),
+    })?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "entry": entry } })))
+}
+
+/// Stops the caller's running timer on the task.
+async fn stop_timer(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(task_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_task_role(&db, task_id, ctx.user.id, ProjectRole::Editor).await?;
+
+    let entry_id: i64 = sqlx::query_scalar(
+        "UPDATE time_entries SET ended_at = GREATEST(NOW(), started_at) WHERE task_id = $1 AND user_id = $2 AND ended_at IS NULL RETURNING id",
+    )
+    .bind(task_id)
+    .bind(ctx.user.id)
+    .fetch_optional(&db)
+    .await?
+    .ok_or(Error::NoRunningTimer)?;
+
+    let entry = fetch_entry(&db, task_id, entry_id).await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "entry": entry } })))
+}
+
+/// The caller's running timer, if any.
+async fn get_running_timer(ctx: Ctx, State(db): State<Db>) -> Result<Json<Value>> {
+    let entry: Option<TimeEntry> = sqlx::query_as(&format!(
+        "{ENTRY_SELECT} WHERE te.user_id = $1 AND te.ended_at IS NULL"
+    ))
+    .bind(ctx.user.id)
+    .fetch_optional(&db)
+    .await?;
+
+    Ok(Json(json!({ "status": "success", "data": { "entry": entry } })))
+}
+
+async fn fetch_entry(db: &Db, task_id: i64, entry_id: i64) -> Result<TimeEntry> {
+    sqlx::query_as::<_, TimeEntry>(&format!(
+        "{ENTRY_SELECT} WHERE te.id = $1 AND te.task_id = $2"
+    ))
+    .bind(entry_id)
+    .bind(task_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(Error::TimeEntryNotFound)
+}
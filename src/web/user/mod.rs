@@ -1,14 +1,28 @@
 pub mod routes;
 
 use serde::{Deserialize, Serialize};
+use sqlx::Type;
 use utoipa::ToSchema;
 
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq, Default)]
+#[sqlx(type_name = "user_role", rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+}
+
 #[derive(Debug, Deserialize, Serialize, sqlx::FromRow, Default, Clone, ToSchema)]
 pub struct User {
     pub id: i64,
     pub username: String,
     #[serde(skip)]
     pub password: Option<String>,
+    #[serde(skip)]
+    #[sqlx(default)]
+    pub token_generation: i32,
+    #[sqlx(default)]
+    pub role: Role,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -16,8 +30,14 @@ pub struct UpdateUserPayload {
     pub username: String,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateUserRolePayload {
+    pub role: Role,
+}
+
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct UserForList {
     id: i64,
     username: String,
+    role: Role,
 }
@@ -1,28 +1,30 @@
 use axum::{
     extract::{Path, State},
-    routing::get,
+    routing::{get, put},
     Json, Router,
 };
 use serde_json::{json, Value};
 
 use crate::errors::{Error, Result};
 use crate::web::db::Db;
-use crate::web::user::{User, UpdateUserPayload};
+use crate::web::guard::{AdminCtx, SelfOrAdmin};
+use crate::web::user::{UpdateUserPayload, UpdateUserRolePayload, User, UserForList};
 
 pub fn routes(db: Db) -> Router {
     Router::new()
         .route("/users", get(list_users))
         .route(
-            "/users/:id",
+            "/users/{id}",
             get(get_user)
                 .put(update_user)
                 .delete(delete_user),
         )
+        .route("/users/{id}/role", put(update_user_role))
         .with_state(db)
 }
 
 async fn list_users(State(db): State<Db>) -> Result<Json<Value>> {
-    let users = sqlx::query_as::<_, User>("SELECT id, username FROM users")
+    let users = sqlx::query_as::<_, UserForList>("SELECT id, username, role FROM users")
         .fetch_all(&db)
         .await?;
 
@@ -35,7 +37,7 @@ async fn list_users(State(db): State<Db>) -> Result<Json<Value>> {
 }
 
 async fn get_user(State(db): State<Db>, Path(user_id): Path<i64>) -> Result<Json<Value>> {
-    let user = sqlx::query_as::<_, User>("SELECT id, username FROM users WHERE id = $1")
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
         .bind(user_id)
         .fetch_optional(&db)
         .await?
@@ -50,12 +52,12 @@ async fn get_user(State(db): State<Db>, Path(user_id): Path<i64>) -> Result<Json
 }
 
 async fn update_user(
+    SelfOrAdmin(user_id): SelfOrAdmin,
     State(db): State<Db>,
-    Path(user_id): Path<i64>,
     Json(payload): Json<UpdateUserPayload>,
 ) -> Result<Json<Value>> {
     let user = sqlx::query_as::<_, User>(
-        "UPDATE users SET username = $1 WHERE id = $2 RETURNING id, username",
+        "UPDATE users SET username = $1 WHERE id = $2 RETURNING *",
     )
     .bind(payload.username)
     .bind(user_id)
@@ -71,7 +73,36 @@ async fn update_user(
     })))
 }
 
-async fn delete_user(State(db): State<Db>, Path(user_id): Path<i64>) -> Result<Json<Value>> {
+async fn update_user_role(
+    AdminCtx(ctx): AdminCtx,
+    State(db): State<Db>,
+    Path(user_id): Path<i64>,
+    Json(payload): Json<UpdateUserRolePayload>,
+) -> Result<Json<Value>> {
+    // Guard against an admin locking themselves out.
+    if ctx.user.id == user_id {
+        return Err(Error::UserUnauthorized);
+    }
+
+    let user = sqlx::query_as::<_, User>("UPDATE users SET role = $1 WHERE id = $2 RETURNING *")
+        .bind(payload.role)
+        .bind(user_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(Error::UserNotFound)?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "user": user
+        }
+    })))
+}
+
+async fn delete_user(
+    SelfOrAdmin(user_id): SelfOrAdmin,
+    State(db): State<Db>,
+) -> Result<Json<Value>> {
     let rows_affected = sqlx::query("DELETE FROM users WHERE id = $1")
         .bind(user_id)
         .execute(&db)
@@ -0,0 +1,164 @@
+pub mod routes;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor, Type};
+use utoipa::ToSchema;
+
+use crate::{Error, Result};
+
+/// What a workflow status means for the rest of the system: blockers,
+/// recurrence and progress roll-ups only look at the category.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "status_category", rename_all = "snake_case")]
+pub enum StatusCategory {
+    Todo,
+    Doing,
+    Done,
+}
+
+/// The statuses every new project starts with.
+const DEFAULT_WORKFLOW: [(&str, &str, StatusCategory); 3] = [
+    ("pending", "Pending", StatusCategory::Todo),
+    ("in_progress", "In Progress", StatusCategory::Doing),
+    ("completed", "Completed", StatusCategory::Done),
+];
+
+#[derive(Debug, Serialize, FromRow, Clone, ToSchema)]
+pub struct WorkflowStatus {
+    pub id: i64,
+    pub project_id: i64,
+    /// Identifier used as a task's `status`.
+    pub key: String,
+    pub name: String,
+    pub category: StatusCategory,
+    pub position: i32,
+    /// Tasks may only leave this status through its transitions; with none,
+    /// the status is terminal.
+    pub restricted: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWorkflowStatusPayload {
+    pub key: String,
+    pub name: String,
+    pub category: StatusCategory,
+    /// Defaults to after the last status.
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWorkflowStatusPayload {
+    /// Renaming a key updates every task using it.
+    pub key: Option<String>,
+    pub name: Option<String>,
+    pub category: Option<StatusCategory>,
+    pub position: Option<i32>,
+}
+
+/// An allowed status change, by status key.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
+pub struct WorkflowTransition {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetTransitionsPayload {
+    /// Status keys a task may move to; an empty list makes the status terminal.
+    pub to: Vec<String>,
+}
+
+/// Keys are short snake_case identifiers such as `in_review`.
+pub fn validate_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && key.len() <= 50
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(Error::InvalidStatusKey);
+    }
+    Ok(())
+}
+
+pub(crate) async fn create_default_workflow(conn: &mut PgConnection, project_id: i64) -> Result<()> {
+    for (position, (key, name, category)) in DEFAULT_WORKFLOW.into_iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO workflow_statuses (project_id, key, name, category, position) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(project_id)
+        .bind(key)
+        .bind(name)
+        .bind(category)
+        .bind(position as i32)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Looks up `key` in the project's workflow.
+pub(crate) async fn resolve_status<'e>(
+    executor: impl PgExecutor<'e>,
+    project_id: i64,
+    key: &str,
+) -> Result<WorkflowStatus> {
+    sqlx::query_as::<_, WorkflowStatus>(
+        "SELECT * FROM workflow_statuses WHERE project_id = $1 AND key = $2",
+    )
+    .bind(project_id)
+    .bind(key)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(Error::InvalidTaskStatus)
+}
+
+/// Status new tasks start in: the first `Todo` status of the workflow, or its
+/// first status if it has no `Todo` one.
+pub(crate) async fn initial_status<'e>(executor: impl PgExecutor<'e>, project_id: i64) -> Result<String> {
+    sqlx::query_scalar(
+        "SELECT key FROM workflow_statuses WHERE project_id = $1 ORDER BY category <> 'todo', position, id LIMIT 1",
+    )
+    .bind(project_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(Error::InvalidTaskStatus)
+}
+
+/// Fails when `from` is a restricted status and `to` is not one of its
+/// transitions. An unrestricted status allows any change.
+pub(crate) async fn ensure_transition_allowed<'e>(
+    executor: impl PgExecutor<'e>,
+    project_id: i64,
+    from: &str,
+    to: &str,
+) -> Result<()> {
+    if from == to {
+        return Ok(());
+    }
+
+    // `None` when `from` is unrestricted.
+    let allowed: Option<Vec<String>> = sqlx::query_scalar(
+        "SELECT ARRAY(SELECT ts.key FROM workflow_transitions wt \
+            JOIN workflow_statuses ts ON ts.id = wt.to_status_id \
+            WHERE wt.from_status_id = fs.id ORDER BY ts.position, ts.id) \
+         FROM workflow_statuses fs WHERE fs.project_id = $1 AND fs.key = $2 AND fs.restricted",
+    )
+    .bind(project_id)
+    .bind(from)
+    .fetch_optional(executor)
+    .await?;
+
+    let Some(allowed) = allowed else {
+        return Ok(());
+    };
+    if allowed.iter().any(|key| key == to) {
+        return Ok(());
+    }
+
+    Err(Error::TransitionNotAllowed {
+        from: from.to_string(),
+        to: to.to_string(),
+        allowed,
+    })
+}
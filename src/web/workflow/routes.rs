@@ -0,0 +1,276 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+
+use crate::ctx::Ctx;
+use crate::errors::{Error, Result};
+use crate::web::db::Db;
+use crate::web::member::{require_project_role, ProjectRole};
+use crate::web::workflow::{
+    validate_key, CreateWorkflowStatusPayload, SetTransitionsPayload, UpdateWorkflowStatusPayload,
+    WorkflowStatus, WorkflowTransition,
+};
+
+pub fn routes(db: Db) -> Router {
+    Router::new()
+        .route("/projects/{id}/workflow", get(get_workflow))
+        .route("/projects/{id}/workflow/statuses", post(create_status))
+        .route(
+            "/projects/{id}/workflow/statuses/{status_id}",
+            put(update_status).delete(delete_status),
+        )
+        .route(
+            "/projects/{id}/workflow/statuses/{status_id}/transitions",
+            put(set_transitions).delete(clear_transitions),
+        )
+        .with_state(db)
+}
+
+const TRANSITION_SELECT: &str = "SELECT fs.key AS \"from\", ts.key AS \"to\" FROM workflow_transitions wt JOIN workflow_statuses fs ON fs.id = wt.from_status_id JOIN workflow_statuses ts ON ts.id = wt.to_status_id";
+
+/// Lists the project's statuses in board order and its allowed transitions.
+async fn get_workflow(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Viewer).await?;
+
+    let statuses: Vec<WorkflowStatus> = sqlx::query_as(
+        "SELECT * FROM workflow_statuses WHERE project_id = $1 ORDER BY position, id",
+    )
+    .bind(project_id)
+    .fetch_all(&db)
+    .await?;
+
+    let transitions: Vec<WorkflowTransition> = sqlx::query_as(&format!(
+        "{TRANSITION_SELECT} WHERE fs.project_id = $1 ORDER BY fs.position, fs.id, ts.position, ts.id"
+    ))
+    .bind(project_id)
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "statuses": statuses, "transitions": transitions }
+    })))
+}
+
+async fn create_status(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path(project_id): Path<i64>,
+    Json(payload): Json<CreateWorkflowStatusPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    validate_key(&payload.key)?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(Error::StatusNameEmpty);
+    }
+
+    let status = sqlx::query_as::<_, WorkflowStatus>(
+        "INSERT INTO workflow_statuses (project_id, key, name, category, position) \
+         VALUES ($1, $2, $3, $4, COALESCE($5, (SELECT COALESCE(MAX(position) + 1, 0) FROM workflow_statuses WHERE project_id = $1))) \
+         ON CONFLICT (project_id, key) DO NOTHING RETURNING *",
+    )
+    .bind(project_id)
+    .bind(&payload.key)
+    .bind(name)
+    .bind(payload.category)
+    .bind(payload.position)
+    .fetch_optional(&db)
+    .await?
+    .ok_or(Error::WorkflowStatusAlreadyExists)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "status": status } })))
+}
+
+async fn update_status(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, status_id)): Path<(i64, i64)>,
+    Json(payload): Json<UpdateWorkflowStatusPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    if let Some(key) = &payload.key {
+        validate_key(key)?;
+    }
+    let name = payload.name.as_deref().map(str::trim);
+    if name == Some("") {
+        return Err(Error::StatusNameEmpty);
+    }
+
+    let status = sqlx::query_as::<_, WorkflowStatus>(
+        "UPDATE workflow_statuses SET key = COALESCE($1, key), name = COALESCE($2, name), category = COALESCE($3, category), position = COALESCE($4, position) WHERE id = $5 AND project_id = $6 RETURNING *",
+    )
+    .bind(payload.key)
+    .bind(name)
+    .bind(payload.category)
+    .bind(payload.position)
+    .bind(status_id)
+    .bind(project_id)
+    .fetch_optional(&db)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => Error::WorkflowStatusAlreadyExists,
+        err => Error::SqlxError(err),
+    })?
+    .ok_or(Error::WorkflowStatusNotFound)?;
+
+    Ok(Json(json!({ "status": "success", "data": { "status": status } })))
+}
+
+/// Only statuses no task is in can be deleted, and never the last one.
+async fn delete_status(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, status_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let mut tx = db.begin().await?;
+
+    sqlx::query("SELECT id FROM projects WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let other_statuses: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM workflow_statuses WHERE project_id = $1 AND id <> $2",
+    )
+    .bind(project_id)
+    .bind(status_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if other_statuses == 0 {
+        return Err(Error::LastWorkflowStatus);
+    }
+
+    let rows_affected = sqlx::query("DELETE FROM workflow_statuses WHERE id = $1 AND project_id = $2")
+        .bind(status_id)
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => Error::WorkflowStatusInUse,
+            err => Error::SqlxError(err),
+        })?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::WorkflowStatusNotFound);
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(json!({ "status": "success", "message": "Status deleted" })))
+}
+
+/// Restricts the status to the given transitions, replacing any previous
+/// ones. An empty list makes the status terminal.
+async fn set_transitions(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, status_id)): Path<(i64, i64)>,
+    Json(payload): Json<SetTransitionsPayload>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let mut tx = db.begin().await?;
+
+    let from_key: String = sqlx::query_scalar(
+        "UPDATE workflow_statuses SET restricted = TRUE WHERE id = $1 AND project_id = $2 RETURNING key",
+    )
+    .bind(status_id)
+    .bind(project_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(Error::WorkflowStatusNotFound)?;
+
+    let mut to_keys = payload.to;
+    to_keys.retain(|key| *key != from_key);
+    to_keys.sort_unstable();
+    to_keys.dedup();
+
+    let to_ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM workflow_statuses WHERE project_id = $1 AND key = ANY($2)",
+    )
+    .bind(project_id)
+    .bind(&to_keys)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if to_ids.len() != to_keys.len() {
+        return Err(Error::InvalidTaskStatus);
+    }
+
+    sqlx::query("DELETE FROM workflow_transitions WHERE from_status_id = $1")
+        .bind(status_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO workflow_transitions (from_status_id, to_status_id) SELECT $1, UNNEST($2::BIGINT[])",
+    )
+    .bind(status_id)
+    .bind(&to_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    let transitions: Vec<WorkflowTransition> = sqlx::query_as(&format!(
+        "{TRANSITION_SELECT} WHERE wt.from_status_id = $1 ORDER BY ts.position, ts.id"
+    ))
+    .bind(status_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "restricted": true, "transitions": transitions }
+    })))
+}
+
+/// Lifts the restriction, so tasks may move from this status to any other.
+async fn clear_transitions(
+    ctx: Ctx,
+    State(db): State<Db>,
+    Path((project_id, status_id)): Path<(i64, i64)>,
+) -> Result<Json<Value>> {
+    require_project_role(&db, project_id, ctx.user.id, ProjectRole::Owner).await?;
+
+    let mut tx = db.begin().await?;
+
+    let rows_affected = sqlx::query(
+        "UPDATE workflow_statuses SET restricted = FALSE WHERE id = $1 AND project_id = $2",
+    )
+    .bind(status_id)
+    .bind(project_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(Error::WorkflowStatusNotFound);
+    }
+
+    sqlx::query("DELETE FROM workflow_transitions WHERE from_status_id = $1")
+        .bind(status_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": { "restricted": false, "transitions": [] }
+    })))
+}
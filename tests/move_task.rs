@@ -0,0 +1,158 @@
+//! Moving a task to another project through `PUT /api/tasks/{id}`. Like
+//! `quick_dev`, this runs against a server on localhost:8080.
+
+use anyhow::Result;
+use httpc_test::{new_client, new_client_with_reqwest, Client};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BASE_URL: &str = "http://localhost:8080";
+
+/// Registers a fresh user and returns its id with a client logged in as it.
+async fn new_user(name: &str) -> Result<(i64, Client)> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let credentials = json!({ "username": format!("{name}{nanos}"), "password": "password123" });
+
+    let hc = new_client(BASE_URL)?;
+    let user_id = hc
+        .do_post("/api/register", credentials.clone())
+        .await?
+        .json_value::<i64>("/data/user/id")?;
+    let token = hc
+        .do_post("/api/login", credentials)
+        .await?
+        .json_value::<String>("/data/token")?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}"))?);
+    let hc = new_client_with_reqwest(BASE_URL, reqwest::Client::builder().default_headers(headers))?;
+    Ok((user_id, hc))
+}
+
+async fn post(hc: &Client, url: &str, body: Value) -> Result<Value> {
+    let res = hc.do_post(url, body).await?;
+    assert!(res.status().is_success(), "POST {url}: {}", res.status());
+    Ok(res.json_body()?)
+}
+
+async fn create_project(hc: &Client, name: &str) -> Result<i64> {
+    Ok(post(hc, "/api/projects", json!({ "name": name })).await?["data"]["project"]["id"]
+        .as_i64()
+        .unwrap())
+}
+
+async fn create_label(hc: &Client, project_id: i64, name: &str) -> Result<i64> {
+    let url = format!("/api/projects/{project_id}/labels");
+    Ok(post(hc, &url, json!({ "name": name })).await?["data"]["label"]["id"]
+        .as_i64()
+        .unwrap())
+}
+
+async fn add_member(hc: &Client, project_id: i64, user_id: i64, role: &str) -> Result<()> {
+    let url = format!("/api/projects/{project_id}/members");
+    post(hc, &url, json!({ "user_id": user_id, "role": role })).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_task_between_projects() -> Result<()> {
+    let (owner_id, owner) = new_user("mover").await?;
+    let (editor_id, editor) = new_user("editor").await?;
+    let (viewer_id, viewer) = new_user("viewer").await?;
+
+    let source = create_project(&owner, "Move source").await?;
+    let target = create_project(&owner, "Move target").await?;
+    // Editor can write to the source only; viewer can write to the target only.
+    add_member(&owner, source, editor_id, "Editor").await?;
+    add_member(&owner, target, editor_id, "Viewer").await?;
+    add_member(&owner, source, viewer_id, "Viewer").await?;
+    add_member(&owner, target, viewer_id, "Editor").await?;
+
+    // The target has no `in_review` status, only the default `Doing` one.
+    let url = format!("/api/projects/{source}/workflow/statuses");
+    post(&owner, &url, json!({ "key": "in_review", "name": "In Review", "category": "Doing" })).await?;
+    let source_bug = create_label(&owner, source, "bug").await?;
+    let source_ui = create_label(&owner, source, "ui").await?;
+    let target_bug = create_label(&owner, target, "bug").await?;
+
+    let task = post(
+        &owner,
+        "/api/tasks",
+        json!({
+            "project_id": source,
+            "title": "Filed in the wrong project",
+            "assignee_ids": [owner_id, editor_id],
+            "label_ids": [source_bug, source_ui],
+        }),
+    )
+    .await?;
+    let task_id = task["data"]["task"]["id"].as_i64().unwrap();
+    let task_url = format!("/api/tasks/{task_id}");
+    let res = owner.do_put(&task_url, json!({ "status": "in_review" })).await?;
+    assert!(res.status().is_success());
+
+    let move_payload = json!({ "project_id": target });
+
+    // Write access is needed on the target project...
+    let res = editor.do_put(&task_url, move_payload.clone()).await?;
+    assert_eq!(res.status(), 403);
+    // ...and on the source project.
+    let res = viewer.do_put(&task_url, move_payload.clone()).await?;
+    assert_eq!(res.status(), 403);
+    let task = owner.do_get(&task_url).await?.json_body()?;
+    assert_eq!(task["data"]["task"]["project_id"], json!(source));
+
+    let res = owner.do_put(&task_url, move_payload).await?;
+    assert!(res.status().is_success(), "move failed: {}", res.status());
+    let moved = &res.json_body()?["data"]["task"];
+
+    assert_eq!(moved["project_id"], json!(target));
+    // No `in_review` in the target: the first `Doing` status is used instead.
+    assert_eq!(moved["status"], json!("in_progress"));
+    // Labels are matched by name; `ui` does not exist in the target.
+    let label_ids: Vec<i64> = moved["labels"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|label| label["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(label_ids, [target_bug]);
+    // The editor is only a viewer of the target, but still a member.
+    assert_eq!(moved["assignee_ids"], json!([owner_id, editor_id]));
+
+    let history = owner.do_get(&format!("{task_url}/history")).await?.json_body()?;
+    let changes = &history["data"]["activities"][0]["changes"];
+    assert_eq!(changes["project_id"], json!({ "from": source, "to": target }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_task_drops_assignees_outside_target() -> Result<()> {
+    let (owner_id, owner) = new_user("mover").await?;
+    let (member_id, _) = new_user("member").await?;
+
+    let source = create_project(&owner, "Move source").await?;
+    let target = create_project(&owner, "Move target").await?;
+    add_member(&owner, source, member_id, "Editor").await?;
+
+    let task = post(
+        &owner,
+        "/api/tasks",
+        json!({ "project_id": source, "title": "Moving", "assignee_ids": [owner_id, member_id] }),
+    )
+    .await?;
+    let task_id = task["data"]["task"]["id"].as_i64().unwrap();
+
+    let res = owner
+        .do_put(&format!("/api/tasks/{task_id}"), json!({ "project_id": target }))
+        .await?;
+    assert!(res.status().is_success(), "move failed: {}", res.status());
+    let moved = &res.json_body()?["data"]["task"];
+    assert_eq!(moved["project_id"], json!(target));
+    assert_eq!(moved["status"], json!("pending"));
+    assert_eq!(moved["assignee_ids"], json!([owner_id]));
+
+    Ok(())
+}